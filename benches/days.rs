@@ -0,0 +1,34 @@
+use std::{env, process::ExitCode};
+
+use advent_of_code_2023 as aoc;
+
+const RUNS: u32 = 10;
+
+fn main() -> ExitCode {
+  // `cargo bench` passes `--bench`; any other argument filters by day.
+  let filter = env::args()
+    .skip(1)
+    .filter(|arg| !arg.starts_with("--"))
+    .map(|arg| arg.parse::<u8>())
+    .collect::<Result<Vec<_>, _>>();
+
+  let Ok(filter) = filter else {
+    eprintln!("Usage: cargo bench -- [day...]");
+    return ExitCode::from(2);
+  };
+
+  let solvers = aoc::DAYS
+    .iter()
+    .filter(|solver| filter.is_empty() || filter.contains(&solver.day));
+
+  match aoc::Timings::measure_days(solvers, RUNS) {
+    Ok(timings) => {
+      print!("{}", aoc::TimingTable(&timings));
+      ExitCode::SUCCESS
+    }
+    Err(err) => {
+      eprintln!("{err}");
+      ExitCode::FAILURE
+    }
+  }
+}
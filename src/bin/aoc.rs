@@ -0,0 +1,163 @@
+use std::{
+  env, fs,
+  io::{self, Read},
+  process::ExitCode,
+  str::FromStr,
+};
+
+use advent_of_code_2023 as aoc;
+
+const USAGE: &str = "Usage: aoc --day <1-19> --part <1|2> [--input <path|->]
+       aoc --bench [--day <1-19>] [--runs <n>]";
+
+const DEFAULT_RUNS: u32 = 10;
+
+enum Args {
+  Solve {
+    day: u8,
+    part: u8,
+    input: Option<String>,
+  },
+  Bench {
+    day: Option<u8>,
+    runs: u32,
+  },
+}
+
+fn parse_value<T: FromStr>(name: &str, value: String) -> Result<T, String> {
+  value
+    .parse()
+    .map_err(|_| format!("Invalid value for {name}: {value}"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+  let mut day = None;
+  let mut part = None;
+  let mut input = None;
+  let mut bench = false;
+  let mut runs = None;
+
+  while let Some(arg) = args.next() {
+    let mut value = |name: &str| {
+      args
+        .next()
+        .ok_or_else(|| format!("Missing value for {name}"))
+    };
+
+    match arg.as_str() {
+      "--day" | "-d" => {
+        day = Some(parse_value("--day", value("--day")?)?);
+      }
+      "--part" | "-p" => {
+        part = Some(parse_value("--part", value("--part")?)?);
+      }
+      "--input" | "-i" => {
+        input = Some(value("--input")?);
+      }
+      "--bench" => {
+        bench = true;
+      }
+      "--runs" => {
+        runs = Some(parse_value("--runs", value("--runs")?)?);
+      }
+      arg => {
+        return Err(format!("Unexpected argument: {arg}"));
+      }
+    }
+  }
+
+  if bench {
+    if part.is_some() || input.is_some() {
+      return Err("--bench runs both parts on the bundled inputs".into());
+    }
+
+    return Ok(Args::Bench {
+      day,
+      runs: runs.unwrap_or(DEFAULT_RUNS),
+    });
+  }
+
+  if runs.is_some() {
+    return Err("--runs requires --bench".into());
+  }
+
+  Ok(Args::Solve {
+    day: day.ok_or("Missing --day")?,
+    part: part.ok_or("Missing --part")?,
+    input: input.filter(|path| path != "-"),
+  })
+}
+
+fn read_input(path: Option<&str>) -> io::Result<String> {
+  match path {
+    Some(path) => fs::read_to_string(path),
+    None => {
+      let mut input = String::new();
+      io::stdin().read_to_string(&mut input)?;
+      Ok(input)
+    }
+  }
+}
+
+fn bench(day: Option<u8>, runs: u32) -> ExitCode {
+  let solvers = match day {
+    Some(day) => match aoc::day(day) {
+      Some(solver) => std::slice::from_ref(solver),
+      None => {
+        eprintln!("No solution for day {day}");
+        return ExitCode::FAILURE;
+      }
+    },
+    None => aoc::DAYS,
+  };
+
+  match aoc::Timings::measure_days(solvers, runs) {
+    Ok(timings) => {
+      print!("{}", aoc::TimingTable(&timings));
+      ExitCode::SUCCESS
+    }
+    Err(err) => {
+      eprintln!("{err}");
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn solve(day: u8, part: u8, input: Option<&str>) -> ExitCode {
+  // Checked before reading the input, which may block on stdin.
+  let Some(solve_part) = aoc::day(day).and_then(|solver| solver.part(part))
+  else {
+    eprintln!("No solution for day {day} part {part}");
+    return ExitCode::FAILURE;
+  };
+
+  let input = match read_input(input) {
+    Ok(input) => input,
+    Err(err) => {
+      eprintln!("Failed to read input: {err}");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  match solve_part(&input) {
+    Ok(answer) => {
+      println!("{answer}");
+      ExitCode::SUCCESS
+    }
+    Err(err) => {
+      eprintln!("Invalid input: {err}");
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn main() -> ExitCode {
+  match parse_args(env::args().skip(1)) {
+    Ok(Args::Solve { day, part, input }) => solve(day, part, input.as_deref()),
+    Ok(Args::Bench { day, runs }) => bench(day, runs),
+    Err(err) => {
+      eprintln!("{err}\n{USAGE}");
+      ExitCode::from(2)
+    }
+  }
+}
@@ -1,9 +1,38 @@
-pub fn part1(input: &str) -> usize {
-  input.len()
+use crate::{utils::ParseError, Solution};
+
+fn parse_input(input: &str) -> Result<Vec<&str>, ParseError> {
+  Ok(input.lines().collect())
+}
+
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+  Ok(parse_input(input)?.len())
 }
 
-pub fn part2(input: &str) -> usize {
-  input.len()
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+  Ok(parse_input(input)?.len())
+}
+
+// Template for a new day: copy this module, rename `Day00` and register it
+// in `DAYS`.
+pub struct Day00;
+
+impl Solution for Day00 {
+  const DAY: u8 = 0;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_input(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -15,13 +44,13 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 0);
-    assert_eq!(part1(INPUT), 0);
+    assert_eq!(part1(INPUT_TEST), Ok(0));
+    assert_eq!(part1(INPUT), Ok(0));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 0);
-    assert_eq!(part2(INPUT), 0);
+    assert_eq!(part2(INPUT_TEST), Ok(0));
+    assert_eq!(part2(INPUT), Ok(0));
   }
 }
@@ -1,31 +1,42 @@
 use regex::Regex;
 
-fn lines(input: &str) -> impl Iterator<Item = &str> {
-  input.trim().lines().map(|line| line.trim())
+use crate::{utils::ParseError, Solution};
+
+// All the parts share: a calibration line can't be blank, whichever kind
+// of digits it is read for.
+fn parse_lines(input: &str) -> Result<Vec<&str>, ParseError> {
+  input
+    .trim()
+    .lines()
+    .map(|line| {
+      let line = line.trim();
+      if line.is_empty() {
+        Err(ParseError::expected(input, line, "a digit"))
+      } else {
+        Ok(line)
+      }
+    })
+    .collect()
 }
 
-pub fn part1(input: &str) -> u32 {
-  lines(input)
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+  parse_lines(input)?
+    .into_iter()
     .map(|line| {
-      let first_digit = line
-        .chars()
-        .find(|c| c.is_ascii_digit())
-        .and_then(|c| c.to_digit(10))
-        .unwrap();
+      let mut digits = line.chars().filter_map(|c| c.to_digit(10));
 
-      let last_digit = line
-        .chars()
-        .filter(|c| c.is_ascii_digit())
-        .last()
-        .and_then(|c| c.to_digit(10))
-        .unwrap();
+      let first_digit = digits
+        .next()
+        .ok_or_else(|| ParseError::expected(input, line, "a digit"))?;
+
+      let last_digit = digits.last().unwrap_or(first_digit);
 
-      first_digit * 10 + last_digit
+      Ok(first_digit * 10 + last_digit)
     })
     .sum()
 }
 
-pub fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> Result<u32, ParseError> {
   let first_digit_pattern =
     Regex::new(r"^.*?(\d|one|two|three|four|five|six|seven|eight|nine).*$")
       .unwrap();
@@ -50,15 +61,16 @@ pub fn part2(input: &str) -> u32 {
   let mut first_digit_locations = first_digit_pattern.capture_locations();
   let mut last_digit_locations = last_digit_pattern.capture_locations();
 
-  lines(input)
+  parse_lines(input)?
+    .into_iter()
     .map(|line| {
       first_digit_pattern
         .captures_read(&mut first_digit_locations, line)
-        .unwrap();
+        .ok_or_else(|| ParseError::expected(input, line, "a digit"))?;
 
       last_digit_pattern
         .captures_read(&mut last_digit_locations, line)
-        .unwrap();
+        .ok_or_else(|| ParseError::expected(input, line, "a digit"))?;
 
       let first_digit = first_digit_locations
         .get(1)
@@ -72,11 +84,32 @@ pub fn part2(input: &str) -> u32 {
         .map(get_digit)
         .unwrap();
 
-      first_digit * 10 + last_digit
+      Ok(first_digit * 10 + last_digit)
     })
     .sum()
 }
 
+pub struct Day01;
+
+impl Solution for Day01 {
+  const DAY: u8 = 1;
+
+  type Part1 = u32;
+  type Part2 = u32;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_lines(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<u32, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<u32, ParseError> {
+    part2(input)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -92,10 +125,10 @@ mod tests {
         a1b2c3d4e5f
         treb7uchet"
       ),
-      142
+      Ok(142)
     );
 
-    assert_eq!(part1(INPUT), 54990);
+    assert_eq!(part1(INPUT), Ok(54990));
   }
 
   #[test]
@@ -110,8 +143,15 @@ mod tests {
         zoneight234
         7pqrstsixteen"
       ),
-      281
+      Ok(281)
     );
-    assert_eq!(part2(INPUT), 54473);
+    assert_eq!(part2(INPUT), Ok(54473));
+  }
+
+  #[test]
+  fn reports_lines_without_digits() {
+    let err = part1("1abc2\ntrebuchet\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 1));
+    assert_eq!(err.snippet, "trebuchet");
   }
 }
@@ -1,81 +1,90 @@
-use std::{collections::HashMap, iter, sync::OnceLock};
+use std::{collections::HashMap, sync::OnceLock};
 
-use regex::{Regex, RegexBuilder};
+use regex::Regex;
 
-use crate::utils::{LendingIterator, RegexExt};
-
-struct Roll<'a> {
-  count: usize,
-  color: &'a str,
-}
+use crate::{
+  utils::{parse_number, LendingIterator, ParseError, RegexExt},
+  Solution,
+};
 
 struct Game<'a> {
   index: usize,
-  subsets: &'a str,
+  max_counts: HashMap<&'a str, usize>,
 }
 
 impl Game<'_> {
-  fn rolls(&self) -> impl Iterator<Item = Roll> {
-    self.subsets.split("; ").flat_map(|subset| {
-      subset.split(", ").map(|group| {
-        let (count, color) = group.split_once(' ').unwrap();
-        let count = count.parse().unwrap();
-        Roll { count, color }
-      })
-    })
+  fn max_count(&self, color: &str) -> usize {
+    self.max_counts.get(color).copied().unwrap_or_default()
   }
 }
 
-fn games(input: &str) -> impl Iterator<Item = Game> {
+fn games(input: &str) -> impl Iterator<Item = Result<Game, ParseError>> {
   let game_pattern = {
     static GAME_PATTERN: OnceLock<Regex> = OnceLock::new();
-    GAME_PATTERN.get_or_init(|| {
-      RegexBuilder::new(r"^Game (\d+): (.*)$")
-        .multi_line(true)
-        .build()
-        .unwrap()
-    })
+    GAME_PATTERN.get_or_init(|| Regex::new(r"^Game (\d+): (.*)$").unwrap())
   };
 
-  let mut it = game_pattern.lending_captures_iter(input);
-
-  iter::from_fn(move || {
-    it.next().map(|c| Game {
-      index: c.get(1).unwrap().parse().unwrap(),
-      subsets: c.get(2).unwrap(),
-    })
+  input.lines().map(|line| {
+    let mut it = game_pattern.lending_captures_iter(line);
+    let c = it.next().ok_or_else(|| {
+      ParseError::expected(input, line, "`Game <id>: <cubes>`")
+    })?;
+
+    let index = parse_number(input, c.get(1).unwrap())?;
+    let mut max_counts = HashMap::new();
+
+    for group in c.get(2).unwrap().split("; ").flat_map(|s| s.split(", ")) {
+      let (count, color) = group.split_once(' ').ok_or_else(|| {
+        ParseError::expected(input, group, "a count and a color")
+      })?;
+      let count: usize = parse_number(input, count)?;
+      let max_count = max_counts.entry(color).or_default();
+      *max_count = count.max(*max_count);
+    }
+
+    Ok(Game { index, max_counts })
   })
 }
 
-pub fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> Result<usize, ParseError> {
   games(input)
-    .filter(|game| {
-      let mut counts = HashMap::new();
+    .map(|game| {
+      let game = game?;
 
-      for roll in game.rolls() {
-        let prev_count = counts.entry(roll.color).or_default();
-        *prev_count = roll.count.max(*prev_count);
-      }
+      let possible = game.max_count("red") <= 12
+        && game.max_count("green") <= 13
+        && game.max_count("blue") <= 14;
 
-      counts["red"] <= 12 && counts["green"] <= 13 && counts["blue"] <= 14
+      Ok(if possible { game.index } else { 0 })
     })
-    .map(|game| game.index)
     .sum()
 }
 
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> Result<usize, ParseError> {
   games(input)
-    .map(|game| {
-      let mut counts = HashMap::new();
+    .map(|game| Ok(game?.max_counts.into_values().product::<usize>()))
+    .sum()
+}
 
-      for roll in game.rolls() {
-        let prev_count = counts.entry(roll.color).or_default();
-        *prev_count = roll.count.max(*prev_count);
-      }
+pub struct Day02;
 
-      counts.into_values().product::<usize>()
-    })
-    .sum()
+impl Solution for Day02 {
+  const DAY: u8 = 2;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    games(input).try_for_each(|game| game.map(drop))
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -87,13 +96,27 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 8);
-    assert_eq!(part1(INPUT), 3035);
+    assert_eq!(part1(INPUT_TEST), Ok(8));
+    assert_eq!(part1(INPUT), Ok(3035));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 2286);
-    assert_eq!(part2(INPUT), 66027);
+    assert_eq!(part2(INPUT_TEST), Ok(2286));
+    assert_eq!(part2(INPUT), Ok(66027));
+  }
+
+  #[test]
+  fn reports_invalid_counts() {
+    let err = part1("Game 1: 3 blue, x red\n").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 17));
+    assert_eq!(err.snippet, "x");
+  }
+
+  #[test]
+  fn reports_unmatched_lines() {
+    let err = part1("Game 1: 3 blue\nGmae 2: 4 blue\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 1));
+    assert_eq!(err.snippet, "Gmae 2: 4 blue");
   }
 }
@@ -1,108 +1,132 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  ops::RangeInclusive,
+  str::FromStr,
+};
+
+use crate::{
+  utils::{parse_number, ParseError},
+  Solution,
+};
+
+struct Number {
+  value: u32,
+  columns: RangeInclusive<usize>,
+  row: usize,
+}
+
+impl Number {
+  fn neighbours(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let columns =
+      self.columns.start().saturating_sub(1)..=self.columns.end() + 1;
+    let rows = self.row.saturating_sub(1)..=self.row + 1;
+    rows.flat_map(move |y| columns.clone().map(move |x| (x, y)))
+  }
+}
+
+struct Schematic {
+  symbols: BTreeMap<(usize, usize), char>,
+  numbers: Vec<Number>,
+}
+
+impl FromStr for Schematic {
+  type Err = ParseError;
+
+  fn from_str(input: &str) -> Result<Self, Self::Err> {
+    let mut symbols = BTreeMap::new();
+    let mut numbers = Vec::new();
 
-pub fn part1(input: &str) -> u32 {
-  let mut chars = BTreeMap::new();
-  let mut numbers = Vec::new();
+    for (y, line) in input.lines().enumerate() {
+      let mut chars = line.char_indices().enumerate().peekable();
 
-  for (y, line) in input.lines().enumerate() {
-    let mut char_iter = line.chars().enumerate().peekable();
-    while let Some((x, char)) = char_iter.next() {
-      chars.insert((x, y), char);
+      while let Some((x, (start, c))) = chars.next() {
+        if !c.is_ascii_digit() {
+          if c != '.' {
+            symbols.insert((x, y), c);
+          }
+          continue;
+        }
 
-      if let Some(digit) = char.to_digit(10) {
-        let mut number = digit;
         let mut last_x = x;
-        while let Some((next_x, next_digit)) =
-          char_iter.peek().and_then(|(next_x, next_char)| {
-            next_char.to_digit(10).map(|digit| (*next_x, digit))
-          })
+        let mut end = start + 1;
+        while let Some(&(next_x, (next_start, _))) =
+          chars.peek().filter(|(_, (_, c))| c.is_ascii_digit())
         {
-          number = 10 * number + next_digit;
           last_x = next_x;
-          char_iter.next().unwrap();
+          end = next_start + 1;
+          chars.next();
         }
 
-        numbers.push((number, x, last_x, y))
+        numbers.push(Number {
+          value: parse_number(input, &line[start..end])?,
+          columns: x..=last_x,
+          row: y,
+        });
       }
     }
-  }
-
-  numbers
-    .into_iter()
-    .filter(|(_number, min_x, max_x, y)| {
-      for x in (if *min_x == 0 { 0 } else { min_x - 1 })..=max_x + 1 {
-        for y in (if *y == 0 { 0 } else { y - 1 })..=y + 1 {
-          if chars
-            .get(&(x, y))
-            .map(|c| c.to_digit(10).is_none() && *c != '.')
-            .unwrap_or(false)
-          {
-            return true;
-          }
-        }
-      }
 
-      false
-    })
-    .map(|(number, _, _, _)| number)
-    .sum()
+    Ok(Self { symbols, numbers })
+  }
 }
 
-pub fn part2(input: &str) -> u32 {
-  enum Entry {
-    Gear,
-    Number(u32),
-  }
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+  let schematic: Schematic = input.parse()?;
+
+  Ok(
+    schematic
+      .numbers
+      .iter()
+      .filter(|number| {
+        number
+          .neighbours()
+          .any(|pos| schematic.symbols.contains_key(&pos))
+      })
+      .map(|number| number.value)
+      .sum(),
+  )
+}
 
-  let mut entries = BTreeMap::new();
+pub fn part2(input: &str) -> Result<u32, ParseError> {
+  let schematic: Schematic = input.parse()?;
 
-  for (y, line) in input.lines().enumerate() {
-    let mut char_iter = line.chars().enumerate().peekable();
-    while let Some((x, char)) = char_iter.next() {
-      if char == '*' {
-        entries.insert((x, y), Entry::Gear);
-      } else if let Some(digit) = char.to_digit(10) {
-        let mut number = digit;
-        let mut last_x = x;
-        while let Some((next_x, next_digit)) =
-          char_iter.peek().and_then(|(next_x, next_char)| {
-            next_char.to_digit(10).map(|digit| (*next_x, digit))
-          })
-        {
-          number = 10 * number + next_digit;
-          last_x = next_x;
-          char_iter.next().unwrap();
-        }
+  let mut gears = BTreeMap::<_, BTreeSet<u32>>::new();
 
-        for x in x..=last_x {
-          entries.insert((x, y), Entry::Number(number));
-        }
+  for number in &schematic.numbers {
+    for pos in number.neighbours() {
+      if schematic.symbols.get(&pos) == Some(&'*') {
+        gears.entry(pos).or_default().insert(number.value);
       }
     }
   }
 
-  entries
-    .iter()
-    .filter_map(|((x, y), entry)| {
-      if let Entry::Gear = *entry {
-        let mut adjacent_numbers = BTreeSet::new();
-
-        for x in (if *x == 0 { 0 } else { x - 1 })..=x + 1 {
-          for y in (if *y == 0 { 0 } else { y - 1 })..=y + 1 {
-            if let Some(Entry::Number(number)) = entries.get(&(x, y)) {
-              adjacent_numbers.insert(*number);
-            }
-          }
-        }
+  Ok(
+    gears
+      .into_values()
+      .filter(|numbers| numbers.len() == 2)
+      .map(|numbers| numbers.into_iter().product::<u32>())
+      .sum(),
+  )
+}
 
-        if adjacent_numbers.len() == 2 {
-          return Some(adjacent_numbers.into_iter().product::<u32>());
-        }
-      }
+pub struct Day03;
+
+impl Solution for Day03 {
+  const DAY: u8 = 3;
 
-      None
-    })
-    .sum()
+  type Part1 = u32;
+  type Part2 = u32;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    input.parse::<Schematic>().map(drop)
+  }
+
+  fn part1(input: &str) -> Result<u32, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<u32, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -114,13 +138,19 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 4361);
-    assert_eq!(part1(INPUT), 546_563);
+    assert_eq!(part1(INPUT_TEST), Ok(4361));
+    assert_eq!(part1(INPUT), Ok(546_563));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 467_835);
-    assert_eq!(part2(INPUT), 91_031_374);
+    assert_eq!(part2(INPUT_TEST), Ok(467_835));
+    assert_eq!(part2(INPUT), Ok(91_031_374));
+  }
+
+  #[test]
+  fn reports_invalid_numbers() {
+    let err = part1("..*\n.99999999999\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 2));
   }
 }
@@ -1,88 +1,144 @@
 use std::sync::OnceLock;
 
 use bit_set::BitSet;
-use regex::{Regex, RegexBuilder};
+use regex::Regex;
+
+use crate::{
+  utils::{parse_number, ParseError},
+  Solution,
+};
 
 #[derive(Debug)]
-struct Card {
+struct Card<'a> {
+  line: &'a str,
   index: usize,
   winning: BitSet<usize>,
   owned: BitSet<usize>,
 }
 
-fn cards(input: &str) -> impl Iterator<Item = Card> + '_ {
+fn cards(input: &str) -> impl Iterator<Item = Result<Card, ParseError>> + '_ {
   let card_pattern = {
     static CARD_PATTERN: OnceLock<Regex> = OnceLock::new();
-    CARD_PATTERN.get_or_init(|| {
-      RegexBuilder::new(r"^Card +(\d+): +(.+) \| +(.*)$")
-        .multi_line(true)
-        .build()
-        .unwrap()
-    })
+    CARD_PATTERN
+      .get_or_init(|| Regex::new(r"^Card +(\d+): +(.+) \| +(.*)$").unwrap())
+  };
+
+  let numbers = |s: &str| {
+    s.split_whitespace()
+      .map(|n| parse_number(input, n))
+      .collect::<Result<_, _>>()
   };
 
-  card_pattern.captures_iter(input).map(|c| Card {
-    index: c.get(1).unwrap().as_str().parse().unwrap(),
-    winning: c
-      .get(2)
-      .unwrap()
-      .as_str()
-      .split_whitespace()
-      .map(|c| c.parse().unwrap())
-      .collect(),
-    owned: c
-      .get(3)
-      .unwrap()
-      .as_str()
-      .split_whitespace()
-      .map(|c| c.parse().unwrap())
-      .collect(),
+  input.lines().enumerate().map(move |(i, line)| {
+    let c = card_pattern.captures(line).ok_or_else(|| {
+      ParseError::expected(input, line, "`Card <id>: <winning> | <owned>`")
+    })?;
+
+    let index = c.get(1).unwrap().as_str();
+    if parse_number::<usize>(input, index)? != i + 1 {
+      return Err(ParseError::expected(
+        input,
+        index,
+        "sequential card numbers",
+      ));
+    }
+
+    Ok(Card {
+      line,
+      index: i + 1,
+      winning: numbers(c.get(2).unwrap().as_str())?,
+      owned: numbers(c.get(3).unwrap().as_str())?,
+    })
   })
 }
 
-pub fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> Result<usize, ParseError> {
   cards(input)
     .map(|card| {
+      let card = card?;
       let matches = card.owned.intersection(&card.winning).count();
-      if matches == 0 {
-        0
-      } else {
-        1 << (matches - 1)
-      }
+      Ok(if matches == 0 { 0 } else { 1 << (matches - 1) })
     })
     .sum()
 }
 
-pub fn part2(input: &str) -> usize {
-  let cards = cards(input).collect::<Vec<_>>();
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+  let cards = cards(input).collect::<Result<Vec<_>, _>>()?;
   let mut card_counts = vec![1; cards.len()];
 
-  for card in cards.into_iter() {
+  for card in cards.iter() {
     let matches = card.owned.intersection(&card.winning).count();
+    if card.index + matches > cards.len() {
+      return Err(ParseError::expected(
+        input,
+        card.line,
+        "copies of cards up to the last one",
+      ));
+    }
+
     for i in 0..matches {
       card_counts[card.index + i] += card_counts[card.index - 1];
     }
   }
 
-  card_counts.into_iter().sum()
+  Ok(card_counts.into_iter().sum())
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+  const DAY: u8 = 4;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    cards(input).try_for_each(|card| card.map(drop))
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::utils::ParseErrorKind::Expected;
 
   const INPUT_TEST: &str = include_str!("input_test.txt");
   const INPUT: &str = include_str!("input.txt");
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 13);
-    assert_eq!(part1(INPUT), 27_059);
+    assert_eq!(part1(INPUT_TEST), Ok(13));
+    assert_eq!(part1(INPUT), Ok(27_059));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 30);
-    assert_eq!(part2(INPUT), 5_744_979);
+    assert_eq!(part2(INPUT_TEST), Ok(30));
+    assert_eq!(part2(INPUT), Ok(5_744_979));
+  }
+
+  #[test]
+  fn reports_invalid_cards() {
+    let error = |input| part2(input).unwrap_err();
+
+    let err = error("Card 1: 41 48 | 41 48\nCard 2: 1 | 2\n");
+    assert_eq!((err.line, err.column), (1, 1));
+    assert_eq!(err.kind, Expected("copies of cards up to the last one"));
+
+    let err = error("Card 0: 1 | 2\n");
+    assert_eq!((err.line, err.column), (1, 6));
+    assert_eq!(err.kind, Expected("sequential card numbers"));
+
+    let err = error("Card 1: 1 | 2\ngarbage line\n");
+    assert_eq!((err.line, err.column), (2, 1));
+    assert_eq!(err.snippet, "garbage line");
   }
 }
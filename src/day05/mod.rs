@@ -1,124 +1,606 @@
-use std::{array, convert::Infallible, mem, str::FromStr};
+use std::ops::Range;
 
-struct SeedsAndTransforms {
-  seeds: Vec<u64>,
-  transforms: Vec<Vec<[u64; 3]>>,
+use crate::{
+  utils::{parse_number, search, ParseError},
+  Solution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+  start: u64,
+  offset: i64,
+}
+
+// A piecewise-linear map over the whole `u64` domain, stored as pieces
+// sorted by start, each extending up to the start of the next one. Values
+// outside any almanac range map to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap {
+  pieces: Vec<Piece>,
+}
+
+impl Default for RangeMap {
+  fn default() -> Self {
+    Self::identity()
+  }
+}
+
+impl RangeMap {
+  pub fn identity() -> Self {
+    Self {
+      pieces: vec![Piece {
+        start: 0,
+        offset: 0,
+      }],
+    }
+  }
+
+  // Where ranges overlap, the one listed first wins.
+  pub fn from_ranges(ranges: &[[u64; 3]]) -> Self {
+    let mut bounds = ranges
+      .iter()
+      .flat_map(|&[_, src, len]| [src, src + len])
+      .chain([0])
+      .collect::<Vec<_>>();
+
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let pieces = bounds.into_iter().map(|start| {
+      let offset = ranges
+        .iter()
+        .find(|&&[_, src, len]| (src..src + len).contains(&start))
+        .map_or(0, |&[dst, src, _]| dst as i64 - src as i64);
+
+      Piece { start, offset }
+    });
+
+    Self::from_pieces(pieces)
+  }
+
+  fn from_pieces(pieces: impl IntoIterator<Item = Piece>) -> Self {
+    let mut merged = Vec::<Piece>::new();
+
+    for piece in pieces {
+      match merged.last() {
+        Some(last) if last.offset == piece.offset => {}
+        _ => merged.push(piece),
+      }
+    }
+
+    Self { pieces: merged }
+  }
+
+  fn piece_index(&self, value: u64) -> usize {
+    self.pieces.partition_point(|piece| piece.start <= value) - 1
+  }
+
+  fn piece_end(&self, index: usize) -> u64 {
+    self
+      .pieces
+      .get(index + 1)
+      .map_or(u64::MAX, |piece| piece.start)
+  }
+
+  pub fn pieces(&self) -> impl Iterator<Item = (Range<u64>, i64)> + '_ {
+    (0..self.pieces.len()).map(|index| {
+      let piece = self.pieces[index];
+      (piece.start..self.piece_end(index), piece.offset)
+    })
+  }
+
+  pub fn get(&self, value: u64) -> u64 {
+    let piece = self.pieces[self.piece_index(value)];
+    value.checked_add_signed(piece.offset).unwrap()
+  }
+
+  // The images of the parts of `range` covered by each piece, in order of
+  // their source.
+  pub fn get_range(
+    &self,
+    range: Range<u64>,
+  ) -> impl Iterator<Item = Range<u64>> + '_ {
+    let first = self.piece_index(range.start);
+
+    (first..self.pieces.len())
+      .take_while(move |&index| self.pieces[index].start < range.end)
+      .map(move |index| {
+        let Piece { start, offset } = self.pieces[index];
+        let start = start.max(range.start);
+        let end = self.piece_end(index).min(range.end);
+        start.checked_add_signed(offset).unwrap()
+          ..end.checked_add_signed(offset).unwrap()
+      })
+      .filter(|range| !range.is_empty())
+  }
+
+  // Every source interval that maps into `range`, sorted and merged.
+  pub fn preimage(&self, range: Range<u64>) -> Vec<Range<u64>> {
+    let mut intervals = Vec::<Range<u64>>::new();
+
+    for (source, offset) in self.pieces() {
+      let image = source.start.checked_add_signed(offset).unwrap()
+        ..source.end.checked_add_signed(offset).unwrap();
+
+      let start = image.start.max(range.start);
+      let end = image.end.min(range.end);
+
+      if start >= end {
+        continue;
+      }
+
+      let interval = start.checked_add_signed(-offset).unwrap()
+        ..end.checked_add_signed(-offset).unwrap();
+
+      match intervals.last_mut() {
+        Some(last) if last.end == interval.start => last.end = interval.end,
+        _ => intervals.push(interval),
+      }
+    }
+
+    intervals
+  }
+
+  // Applies `self` first, then `next`.
+  pub fn then(&self, next: &RangeMap) -> RangeMap {
+    let pieces = self.pieces().flat_map(|(range, offset)| {
+      let image = range.start.checked_add_signed(offset).unwrap()
+        ..range.end.checked_add_signed(offset).unwrap();
+
+      let first = next.piece_index(image.start);
+
+      next.pieces[first..]
+        .iter()
+        .take_while(move |piece| piece.start < image.end)
+        .map(move |piece| Piece {
+          start: piece
+            .start
+            .max(image.start)
+            .checked_add_signed(-offset)
+            .unwrap(),
+          offset: offset + piece.offset,
+        })
+    });
+
+    Self::from_pieces(pieces.collect::<Vec<_>>())
+  }
+}
+
+pub struct CategoryMap<'a> {
+  pub source: &'a str,
+  pub destination: &'a str,
+  pub ranges: Vec<[u64; 3]>,
+  pub map: RangeMap,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap<'a> {
+  pub source: &'a str,
+  pub destination: &'a str,
+  pub first: [u64; 3],
+  pub second: [u64; 3],
+  pub range: Range<u64>,
+}
+
+impl CategoryMap<'_> {
+  // Pairs of entries whose source ranges overlap, where only the first one
+  // listed takes effect.
+  pub fn overlaps(&self) -> impl Iterator<Item = Overlap<'_>> {
+    self.ranges.iter().enumerate().flat_map(move |(i, &first)| {
+      self.ranges[i + 1..].iter().filter_map(move |&second| {
+        let start = first[1].max(second[1]);
+        let end = (first[1] + first[2]).min(second[1] + second[2]);
+
+        (start < end).then_some(Overlap {
+          source: self.source,
+          destination: self.destination,
+          first,
+          second,
+          range: start..end,
+        })
+      })
+    })
+  }
+}
+
+pub struct Almanac<'a> {
+  pub seeds: Vec<u64>,
+  pub maps: Vec<CategoryMap<'a>>,
+}
+
+impl<'a> Almanac<'a> {
+  // The shortest chain of maps leading from `source` to `destination`,
+  // regardless of the order they were listed in.
+  pub fn route(
+    &self,
+    source: &str,
+    destination: &str,
+  ) -> Option<Vec<&CategoryMap<'a>>> {
+    let result = search::bfs(
+      [source],
+      |&category| {
+        self
+          .maps
+          .iter()
+          .filter(move |map| map.source == category)
+          .map(|map| map.destination)
+      },
+      |&category| category == destination,
+    )?;
+
+    let route = result
+      .path
+      .windows(2)
+      .map(|pair| {
+        self
+          .maps
+          .iter()
+          .find(|map| map.source == pair[0] && map.destination == pair[1])
+          .unwrap()
+      })
+      .collect();
+
+    Some(route)
+  }
+
+  pub fn conversion(
+    &self,
+    source: &str,
+    destination: &str,
+  ) -> Option<RangeMap> {
+    let route = self.route(source, destination)?;
+
+    Some(
+      route
+        .into_iter()
+        .fold(RangeMap::identity(), |conversion, step| {
+          conversion.then(&step.map)
+        }),
+    )
+  }
+
+  pub fn convert(
+    &self,
+    source: &str,
+    destination: &str,
+    value: u64,
+  ) -> Option<u64> {
+    Some(self.conversion(source, destination)?.get(value))
+  }
+
+  pub fn convert_ranges(
+    &self,
+    source: &str,
+    destination: &str,
+    ranges: impl IntoIterator<Item = Range<u64>>,
+  ) -> Option<Vec<Range<u64>>> {
+    let conversion = self.conversion(source, destination)?;
+
+    Some(
+      ranges
+        .into_iter()
+        .flat_map(|range| conversion.get_range(range).collect::<Vec<_>>())
+        .collect(),
+    )
+  }
+
+  // Every `source` interval that converts into `range` of `destination`.
+  pub fn sources_of(
+    &self,
+    source: &str,
+    destination: &str,
+    range: Range<u64>,
+  ) -> Option<Vec<Range<u64>>> {
+    Some(self.conversion(source, destination)?.preimage(range))
+  }
+
+  pub fn overlaps(&self) -> impl Iterator<Item = Overlap<'_>> {
+    self.maps.iter().flat_map(|map| map.overlaps())
+  }
+
+  fn seed_ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+    self.seeds.chunks(2).map(|c| c[0]..c[0] + c[1])
+  }
 }
 
-impl FromStr for SeedsAndTransforms {
-  type Err = Infallible;
+impl<'a> TryFrom<&'a str> for Almanac<'a> {
+  type Error = ParseError;
 
-  fn from_str(s: &str) -> Result<Self, Self::Err> {
+  fn try_from(s: &'a str) -> Result<Self, Self::Error> {
     let mut groups_iter = s.trim_end().split("\n\n");
 
-    let seeds = groups_iter
-      .next()
-      .unwrap()
+    let seeds_line = groups_iter.next().unwrap_or_default();
+
+    let seeds = seeds_line
       .strip_prefix("seeds: ")
-      .unwrap()
+      .ok_or_else(|| ParseError::expected(s, seeds_line, "`seeds: `"))?
       .split_whitespace()
-      .map(|s| s.parse().unwrap())
-      .collect();
+      .map(|n| parse_number(s, n))
+      .collect::<Result<_, _>>()?;
 
-    let transforms = groups_iter
+    let maps = groups_iter
       .map(|group| {
-        group
-          .lines()
-          .skip(1)
+        let mut lines = group.lines();
+        let header = lines.next().unwrap_or_default();
+
+        let (source, destination) = header
+          .strip_suffix(" map:")
+          .and_then(|categories| categories.split_once("-to-"))
+          .ok_or_else(|| {
+            ParseError::expected(s, header, "`<source>-to-<destination> map:`")
+          })?;
+
+        let ranges = lines
           .map(|line| {
-            let mut iter = line.split_whitespace().map(|s| s.parse().unwrap());
-            array::from_fn(|_| iter.next().unwrap())
-          })
-          .collect()
-      })
-      .collect();
+            let mut numbers = line.split_whitespace();
 
-    Ok(Self { seeds, transforms })
-  }
-}
+            let (Some(dst), Some(src), Some(len), None) = (
+              numbers.next(),
+              numbers.next(),
+              numbers.next(),
+              numbers.next(),
+            ) else {
+              return Err(ParseError::expected(s, line, "three numbers"));
+            };
 
-pub fn part1(input: &str) -> u64 {
-  let SeedsAndTransforms { seeds, transforms } = input.parse().unwrap();
+            Ok([
+              parse_number(s, dst)?,
+              parse_number(s, src)?,
+              parse_number(s, len)?,
+            ])
+          })
+          .collect::<Result<Vec<_>, _>>()?;
 
-  let mut numbers = seeds;
+        Ok(CategoryMap {
+          source,
+          destination,
+          map: RangeMap::from_ranges(&ranges),
+          ranges,
+        })
+      })
+      .collect::<Result<_, ParseError>>()?;
 
-  for number in &mut numbers {
-    for transform_ranges in &transforms {
-      for &[dst_start, src_start, len] in transform_ranges {
-        if (src_start..src_start + len).contains(number) {
-          *number += dst_start;
-          *number -= src_start;
-          break;
-        }
-      }
-    }
+    Ok(Self { seeds, maps })
   }
+}
 
-  numbers.into_iter().min().unwrap()
+fn seeds_line(input: &str) -> &str {
+  input.lines().next().unwrap_or(input)
 }
 
-pub fn part2(input: &str) -> u64 {
-  let SeedsAndTransforms { seeds, transforms } = input.parse().unwrap();
+fn seed_locations(
+  input: &str,
+  almanac: &Almanac,
+) -> Result<RangeMap, ParseError> {
+  almanac.conversion("seed", "location").ok_or_else(|| {
+    ParseError::expected(
+      input,
+      &input[input.len()..],
+      "a chain of maps from seed to location",
+    )
+  })
+}
 
-  let seed_ranges = seeds
-    .chunks(2)
-    .map(|c| c[0]..c[0] + c[1])
-    .collect::<Vec<_>>();
+pub fn part1(input: &str) -> Result<u64, ParseError> {
+  let almanac = Almanac::try_from(input)?;
+  let conversion = seed_locations(input, &almanac)?;
 
-  let mut ranges = seed_ranges;
-  let mut next_ranges = Vec::with_capacity(ranges.capacity());
+  almanac
+    .seeds
+    .iter()
+    .map(|&seed| conversion.get(seed))
+    .min()
+    .ok_or_else(|| {
+      ParseError::expected(input, seeds_line(input), "at least one seed")
+    })
+}
 
-  for transform_ranges in transforms {
-    'next_range: while let Some(range) = ranges.pop() {
-      for &[dst_start, src_start, len] in &transform_ranges {
-        let transform_range = src_start..src_start + len;
+pub fn part2(input: &str) -> Result<u64, ParseError> {
+  let almanac = Almanac::try_from(input)?;
+  let conversion = seed_locations(input, &almanac)?;
 
-        let max_start = range.start.max(transform_range.start);
-        let min_end = range.end.min(transform_range.end);
+  if almanac.seeds.len() % 2 != 0 {
+    let seeds_line = seeds_line(input);
+    return Err(ParseError::expected(input, seeds_line, "pairs of numbers"));
+  }
 
-        if max_start < min_end {
-          if range.start < max_start {
-            ranges.push(range.start..max_start);
-          }
+  almanac
+    .seed_ranges()
+    .flat_map(|range| conversion.get_range(range).collect::<Vec<_>>())
+    .map(|range| range.start)
+    .min()
+    .ok_or_else(|| {
+      ParseError::expected(input, seeds_line(input), "at least one seed")
+    })
+}
 
-          if min_end < range.end {
-            ranges.push(min_end..range.end);
-          }
+pub struct Day05;
 
-          next_ranges.push(
-            max_start + dst_start - transform_range.start
-              ..min_end + dst_start - transform_range.start,
-          );
+impl Solution for Day05 {
+  const DAY: u8 = 5;
 
-          continue 'next_range;
-        }
-      }
+  type Part1 = u64;
+  type Part2 = u64;
 
-      next_ranges.push(range);
-    }
+  fn parse(input: &str) -> Result<(), ParseError> {
+    Almanac::try_from(input).map(drop)
+  }
 
-    mem::swap(&mut ranges, &mut next_ranges);
+  fn part1(input: &str) -> Result<u64, ParseError> {
+    part1(input)
   }
 
-  ranges.into_iter().map(|range| range.start).min().unwrap()
+  fn part2(input: &str) -> Result<u64, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::utils::ParseErrorKind;
 
   const INPUT_TEST: &str = include_str!("input_test.txt");
   const INPUT: &str = include_str!("input.txt");
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 35);
-    assert_eq!(part1(INPUT), 324_724_204);
+    assert_eq!(part1(INPUT_TEST), Ok(35));
+    assert_eq!(part1(INPUT), Ok(324_724_204));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 46);
-    assert_eq!(part2(INPUT), 104_070_862);
+    assert_eq!(part2(INPUT_TEST), Ok(46));
+    assert_eq!(part2(INPUT), Ok(104_070_862));
+  }
+
+  #[test]
+  fn composes_range_maps() {
+    let almanac = Almanac::try_from(INPUT_TEST).unwrap();
+    let pipeline = almanac.conversion("seed", "location").unwrap();
+
+    for seed in 0..120 {
+      let stepwise = almanac
+        .maps
+        .iter()
+        .fold(seed, |value, step| step.map.get(value));
+      assert_eq!(pipeline.get(seed), stepwise);
+    }
+
+    assert_eq!(
+      almanac
+        .seeds
+        .iter()
+        .map(|&seed| pipeline.get(seed))
+        .collect::<Vec<_>>(),
+      [82, 43, 86, 35]
+    );
+    assert!(pipeline
+      .pieces()
+      .zip(pipeline.pieces().skip(1))
+      .all(|((a, _), (b, _))| a.end == b.start));
+
+    let map = RangeMap::from_ranges(&[[50, 98, 2], [52, 50, 48]]);
+    assert_eq!(
+      map.pieces().collect::<Vec<_>>(),
+      [(0..50, 0), (50..98, 2), (98..100, -48), (100..u64::MAX, 0)]
+    );
+    assert_eq!(
+      map.get_range(40..99).collect::<Vec<_>>(),
+      [40..50, 52..100, 50..51]
+    );
+    assert_eq!(map.then(&RangeMap::identity()), map);
+    assert_eq!(RangeMap::identity().then(&map), map);
+  }
+
+  #[test]
+  fn converts_between_any_categories() {
+    let mut groups = INPUT_TEST.trim_end().split("\n\n").collect::<Vec<_>>();
+    groups[1..].reverse();
+    let shuffled = groups.join("\n\n");
+
+    assert_eq!(part1(&shuffled), Ok(35));
+    assert_eq!(part2(&shuffled), Ok(46));
+
+    let almanac = Almanac::try_from(shuffled.as_str()).unwrap();
+    assert_eq!(almanac.maps[0].source, "humidity");
+    assert_eq!(almanac.convert("seed", "humidity", 79), Some(78));
+    assert_eq!(almanac.convert("light", "light", 12), Some(12));
+    assert_eq!(almanac.convert("location", "seed", 82), None);
+    assert_eq!(almanac.convert("seed", "weather", 79), None);
+
+    let route = almanac.route("fertilizer", "location").unwrap();
+    assert_eq!(
+      route
+        .iter()
+        .map(|step| step.destination)
+        .collect::<Vec<_>>(),
+      ["water", "light", "temperature", "humidity", "location"]
+    );
+    assert_eq!(
+      almanac.convert_ranges("fertilizer", "location", [81..82, 53..54]),
+      Some(vec![82..83, 43..44])
+    );
+  }
+
+  #[test]
+  fn finds_seeds_for_locations() {
+    let almanac = Almanac::try_from(INPUT_TEST).unwrap();
+
+    let seeds = |range| {
+      let intervals = almanac.sources_of("seed", "location", range)?;
+      Some(intervals.into_iter().flatten().collect::<Vec<_>>())
+    };
+
+    assert_eq!(seeds(35..36), Some(vec![13]));
+    assert_eq!(seeds(82..83), Some(vec![79]));
+
+    let conversion = almanac.conversion("seed", "location").unwrap();
+    let seeds = almanac.sources_of("seed", "location", 40..50).unwrap();
+    assert!(seeds.windows(2).all(|pair| pair[0].end < pair[1].start));
+
+    let found = seeds.into_iter().flatten().collect::<Vec<_>>();
+    let expected = (0..200)
+      .filter(|&seed| (40..50).contains(&conversion.get(seed)))
+      .collect::<Vec<_>>();
+    assert_eq!(found, expected);
+  }
+
+  #[test]
+  fn reports_overlapping_ranges() {
+    let almanac = Almanac::try_from(INPUT_TEST).unwrap();
+    assert_eq!(almanac.overlaps().count(), 0);
+
+    let input = "seeds: 1\n\na-to-b map:\n0 10 5\n50 12 5\n90 0 3\n";
+    let almanac = Almanac::try_from(input).unwrap();
+
+    assert_eq!(
+      almanac.overlaps().collect::<Vec<_>>(),
+      [Overlap {
+        source: "a",
+        destination: "b",
+        first: [0, 10, 5],
+        second: [50, 12, 5],
+        range: 12..15,
+      }]
+    );
+    assert_eq!(almanac.convert("a", "b", 13), Some(3));
+  }
+
+  #[test]
+  fn reports_invalid_headers() {
+    let err = part1("seeds: 79 14\n\nseed to soil:\n50 98 2\n").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 1));
+    assert_eq!(err.snippet, "seed to soil:");
+  }
+
+  #[test]
+  fn reports_incomplete_ranges() {
+    let err = part1("seeds: 79 14\n\nseed-to-soil map:\n50 98\n").unwrap_err();
+    assert_eq!((err.line, err.column), (4, 1));
+    assert_eq!(err.snippet, "50 98");
+  }
+
+  #[test]
+  fn reports_missing_seeds_and_maps() {
+    let err =
+      part1("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n").unwrap_err();
+    assert_eq!(
+      err.kind,
+      ParseErrorKind::Expected("a chain of maps from seed to location")
+    );
+
+    let input = "seeds: \n\nseed-to-location map:\n50 98 2\n";
+    let err = part1(input).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::Expected("at least one seed"));
+    assert_eq!((err.line, err.column), (1, 1));
+    assert!(part2(input).is_err());
+
+    let err = part2("seeds: 1 2 3\n\nseed-to-location map:\n0 0 5\n");
+    assert_eq!(
+      err.unwrap_err().kind,
+      ParseErrorKind::Expected("pairs of numbers")
+    );
   }
 }
@@ -1,3 +1,8 @@
+use crate::{
+  utils::{parse_number, ParseError},
+  Solution,
+};
+
 const fn get_count(record_time: u64, record_distance: u64) -> u64 {
   let mut min_time = 0;
   let mut max_time = record_time;
@@ -15,40 +20,67 @@ const fn get_count(record_time: u64, record_distance: u64) -> u64 {
   record_time - min_time * 2 + 1
 }
 
-pub fn part1(input: &str) -> u64 {
-  let mut iter = input.lines().map(|line| {
+fn records(input: &str) -> Result<[Vec<&str>; 2], ParseError> {
+  let mut lines = input.lines();
+
+  let mut numbers = |label: &'static str| {
+    let line = lines.next().unwrap_or_default();
     line
-      .split_whitespace()
-      .skip(1)
-      .map(|s| s.parse::<u64>().unwrap())
-  });
+      .strip_prefix(label)
+      .map(|numbers| numbers.split_whitespace().collect())
+      .ok_or_else(|| ParseError::expected(input, line, label))
+  };
 
-  let times = iter.next().unwrap();
-  let distances = iter.next().unwrap();
+  Ok([numbers("Time:")?, numbers("Distance:")?])
+}
 
-  let records = times.zip(distances);
+pub fn part1(input: &str) -> Result<u64, ParseError> {
+  let [times, distances] = records(input)?;
 
-  records
+  times
+    .into_iter()
+    .zip(distances)
     .map(|(record_time, record_distance)| {
-      get_count(record_time, record_distance)
+      Ok(get_count(
+        parse_number(input, record_time)?,
+        parse_number(input, record_distance)?,
+      ))
     })
     .product()
 }
 
-pub fn part2(input: &str) -> u64 {
-  let mut iter = input.lines().map(|line| {
-    line
-      .split_whitespace()
-      .skip(1)
-      .collect::<String>()
-      .parse::<u64>()
-      .unwrap()
-  });
+pub fn part2(input: &str) -> Result<u64, ParseError> {
+  let [times, distances] = records(input)?;
+
+  let concat = |numbers: Vec<&str>| -> Result<u64, ParseError> {
+    numbers.into_iter().try_fold(0, |acc, n| {
+      let value: u64 = parse_number(input, n)?;
+      Ok(acc * 10u64.pow(n.len() as u32) + value)
+    })
+  };
+
+  Ok(get_count(concat(times)?, concat(distances)?))
+}
 
-  let record_time = iter.next().unwrap();
-  let record_distance = iter.next().unwrap();
+pub struct Day06;
 
-  get_count(record_time, record_distance)
+impl Solution for Day06 {
+  const DAY: u8 = 6;
+
+  type Part1 = u64;
+  type Part2 = u64;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    records(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<u64, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<u64, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -60,13 +92,13 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 288);
-    assert_eq!(part1(INPUT), 3_317_888);
+    assert_eq!(part1(INPUT_TEST), Ok(288));
+    assert_eq!(part1(INPUT), Ok(3_317_888));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 71_503);
-    assert_eq!(part2(INPUT), 24_655_068);
+    assert_eq!(part2(INPUT_TEST), Ok(71_503));
+    assert_eq!(part2(INPUT), Ok(24_655_068));
   }
 }
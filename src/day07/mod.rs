@@ -1,17 +1,38 @@
 use std::{
-  array,
-  fmt::{Display, Write},
+  fmt::{Debug, Display, Write},
   marker::PhantomData,
   str::FromStr,
 };
 
-trait Rules: Eq + Sized {
-  fn rank(card: &Card<Self>) -> usize;
-  fn kind(hand: &Hand<Self>) -> HandKind;
+use crate::{
+  utils::{parse_number, ParseError, ParseErrorKind},
+  Solution,
+};
+
+// A house variant of Camel Cards: how many cards make a hand, how cards
+// rank against each other, which cards are wild, and what kinds of hands
+// there are. Hands compare by kind first, then card by card.
+//
+// Both are packed into a single sort key per hand, so ranks must fit in
+// four bits, kinds must convert to `u8` in order, and hands can hold at
+// most 14 cards. `Hand::new` checks the hand size at compile time, and
+// panics on ranks that don't fit or on hands of any other size.
+pub trait Rules: Eq + Clone + Sized {
+  const HAND_SIZE: usize = 5;
+
+  type Kind: Ord + Copy + Debug + Into<u8>;
+
+  fn rank(card: Card) -> u8;
+
+  fn is_wildcard(_card: Card) -> bool {
+    false
+  }
+
+  fn kind(cards: &[Card]) -> Self::Kind;
 }
 
-#[derive(Eq, PartialEq, Debug, Clone)]
-enum Card<R> {
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub enum Card {
   Two,
   Three,
   Four,
@@ -24,33 +45,51 @@ enum Card<R> {
   JackOrJoker,
   Queen,
   King,
-  Ace(PhantomData<R>),
-}
-
-impl<R: Rules> PartialOrd for Card<R> {
-  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-    Some(self.cmp(other))
-  }
-}
-
-impl<R: Rules> Ord for Card<R> {
-  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    self.rank().cmp(&other.rank())
-  }
+  Ace,
 }
 
-impl<R: Rules> Card<R> {
-  fn rank(&self) -> usize {
-    R::rank(self)
+impl Card {
+  pub const ALL: [Card; 13] = [
+    Card::Two,
+    Card::Three,
+    Card::Four,
+    Card::Five,
+    Card::Six,
+    Card::Seven,
+    Card::Eight,
+    Card::Nine,
+    Card::Ten,
+    Card::JackOrJoker,
+    Card::Queen,
+    Card::King,
+    Card::Ace,
+  ];
+
+  pub fn face_value(self) -> u8 {
+    match self {
+      Card::Two => 2,
+      Card::Three => 3,
+      Card::Four => 4,
+      Card::Five => 5,
+      Card::Six => 6,
+      Card::Seven => 7,
+      Card::Eight => 8,
+      Card::Nine => 9,
+      Card::Ten => 10,
+      Card::JackOrJoker => 11,
+      Card::Queen => 12,
+      Card::King => 13,
+      Card::Ace => 14,
+    }
   }
 }
 
 #[derive(Debug)]
-enum CardError {
+pub enum CardError {
   InvalidChar(char),
 }
 
-impl<R> TryFrom<char> for Card<R> {
+impl TryFrom<char> for Card {
   type Error = CardError;
 
   fn try_from(c: char) -> Result<Self, Self::Error> {
@@ -67,15 +106,15 @@ impl<R> TryFrom<char> for Card<R> {
       'J' => Ok(Self::JackOrJoker),
       'Q' => Ok(Self::Queen),
       'K' => Ok(Self::King),
-      'A' => Ok(Self::Ace(PhantomData)),
+      'A' => Ok(Self::Ace),
       _ => Err(CardError::InvalidChar(c)),
     }
   }
 }
 
-impl<R> From<&Card<R>> for char {
-  fn from(card: &Card<R>) -> Self {
-    match *card {
+impl From<Card> for char {
+  fn from(card: Card) -> Self {
+    match card {
       Card::Two => '2',
       Card::Three => '3',
       Card::Four => '4',
@@ -88,13 +127,13 @@ impl<R> From<&Card<R>> for char {
       Card::JackOrJoker => 'J',
       Card::Queen => 'Q',
       Card::King => 'K',
-      Card::Ace(_) => 'A',
+      Card::Ace => 'A',
     }
   }
 }
 
 #[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Copy, Debug)]
-enum HandKind {
+pub enum HandKind {
   HighCard,
   OnePair,
   TwoPairs,
@@ -104,190 +143,387 @@ enum HandKind {
   FiveOfAKind,
 }
 
+impl From<HandKind> for u8 {
+  fn from(kind: HandKind) -> Self {
+    kind as u8
+  }
+}
+
 impl HandKind {
-  fn from_counts(counts: &[u8]) -> Self {
-    if counts.contains(&5) {
-      return HandKind::FiveOfAKind;
+  // Classifies a hand of any size by its two largest groups of equal
+  // cards, with every wildcard joining the largest group.
+  pub fn from_counts(counts: &[u8], wildcards: u8) -> Self {
+    let mut largest = [0, 0];
+
+    for &count in counts {
+      if count > largest[0] {
+        largest = [count, largest[0]];
+      } else if count > largest[1] {
+        largest[1] = count;
+      }
     }
 
-    if counts.contains(&4) {
-      return HandKind::FourOfAKind;
+    match (largest[0] + wildcards, largest[1]) {
+      (5.., _) => HandKind::FiveOfAKind,
+      (4, _) => HandKind::FourOfAKind,
+      (3, 2..) => HandKind::FullHouse,
+      (3, _) => HandKind::ThreeOfAKind,
+      (2, 2..) => HandKind::TwoPairs,
+      (2, _) => HandKind::OnePair,
+      _ => HandKind::HighCard,
     }
+  }
 
-    if counts.contains(&3) {
-      if counts.contains(&2) {
-        return HandKind::FullHouse;
+  // The kind of `cards` in the standard game, honoring the wildcards of
+  // `R`.
+  pub fn of<R: Rules>(cards: &[Card]) -> Self {
+    let mut counts = [0u8; 15];
+    let mut wildcards = 0;
+
+    for &card in cards {
+      if R::is_wildcard(card) {
+        wildcards += 1;
+      } else {
+        counts[card.face_value() as usize] += 1;
       }
-
-      return HandKind::ThreeOfAKind;
     }
 
-    let pair_count = counts.iter().filter(|count| **count == 2).count();
+    Self::from_counts(&counts, wildcards)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hand<R: Rules> {
+  cards: Vec<Card>,
+  kind: R::Kind,
+  key: u64,
+  _rules: PhantomData<R>,
+}
 
-    if pair_count == 2 {
-      return HandKind::TwoPairs;
+impl<R: Rules> Hand<R> {
+  // Evaluated once per `R`, so that oversized hands fail to compile.
+  const HAND_SIZE: usize = {
+    assert!(
+      R::HAND_SIZE <= 14,
+      "the kind and 4 bits per card must fit in a 64-bit sort key"
+    );
+    R::HAND_SIZE
+  };
+
+  pub fn new(cards: Vec<Card>) -> Self {
+    let hand_size = Self::HAND_SIZE;
+    assert_eq!(cards.len(), hand_size, "a hand must hold {hand_size} cards");
+
+    let kind = R::kind(&cards);
+    let key = cards.iter().fold(u64::from(kind.into()), |key, &card| {
+      let rank = R::rank(card);
+      assert!(rank < 16, "rank {rank} of {card:?} exceeds 4 bits");
+      key << 4 | u64::from(rank)
+    });
+
+    Self {
+      cards,
+      kind,
+      key,
+      _rules: PhantomData,
     }
+  }
+
+  pub fn cards(&self) -> &[Card] {
+    &self.cards
+  }
+
+  pub fn kind(&self) -> R::Kind {
+    self.kind
+  }
+
+  // The kind in the top bits followed by one nibble per card rank, so that
+  // comparing keys compares hands.
+  pub fn key(&self) -> u64 {
+    self.key
+  }
 
-    if pair_count == 1 {
-      return HandKind::OnePair;
+  fn substituted(&self, substitute: Card) -> Vec<Card> {
+    self
+      .cards
+      .iter()
+      .map(|&card| {
+        if R::is_wildcard(card) {
+          substitute
+        } else {
+          card
+        }
+      })
+      .collect()
+  }
+
+  // The card every wildcard stands in for: the one that makes the best
+  // kind of hand under `R`, then the most common one, then the highest
+  // ranked one.
+  pub fn wildcard_substitute(&self) -> Option<Card> {
+    if !self.cards.iter().any(|&card| R::is_wildcard(card)) {
+      return None;
     }
 
-    HandKind::HighCard
+    let count =
+      |card: Card| self.cards.iter().filter(|&&other| other == card).count();
+
+    Card::ALL
+      .into_iter()
+      .filter(|&card| !R::is_wildcard(card))
+      .max_by_key(|&card| {
+        (R::kind(&self.substituted(card)), count(card), R::rank(card))
+      })
+  }
+
+  pub fn played_as(&self) -> Hand<R> {
+    match self.wildcard_substitute() {
+      Some(substitute) => Hand::new(self.substituted(substitute)),
+      None => self.clone(),
+    }
   }
 }
 
-#[derive(Eq, PartialEq, Debug, Clone)]
-struct Hand<R> {
-  cards: [Card<R>; 5],
-  _rules: PhantomData<R>,
+fn parse_cards(s: &str) -> Result<Vec<Card>, ParseError> {
+  s.char_indices()
+    .map(|(i, c)| {
+      Card::try_from(c).map_err(|CardError::InvalidChar(c)| {
+        let snippet = &s[i..i + c.len_utf8()];
+        ParseError::at(s, snippet, ParseErrorKind::InvalidChar(c))
+      })
+    })
+    .collect()
 }
 
 impl<R: Rules> Hand<R> {
-  fn kind(&self) -> HandKind {
-    Rules::kind(self)
+  // `cards` were read from `hand`, a slice of `source`.
+  fn from_cards(
+    source: &str,
+    hand: &str,
+    cards: Vec<Card>,
+  ) -> Result<Self, ParseError> {
+    if cards.len() != R::HAND_SIZE {
+      return Err(ParseError::expected(source, hand, "a full hand of cards"));
+    }
+
+    Ok(Self::new(cards))
   }
 }
 
-impl<R> FromStr for Hand<R> {
-  type Err = CardError;
+impl<R: Rules> FromStr for Hand<R> {
+  type Err = ParseError;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    let mut chars = s.chars();
-    let cards = array::from_fn(|_| chars.next().unwrap().try_into().unwrap());
-    Ok(Self {
-      cards,
-      _rules: PhantomData,
-    })
+    Self::from_cards(s, s, parse_cards(s)?)
   }
 }
 
-impl<R> Display for Hand<R> {
+impl<R: Rules> Display for Hand<R> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    for card in &self.cards {
-      f.write_char(char::from(card))?;
+    for &card in &self.cards {
+      f.write_char(card.into())?;
     }
     Ok(())
   }
 }
 
+impl<R: Rules> PartialEq for Hand<R> {
+  fn eq(&self, other: &Self) -> bool {
+    self.key == other.key
+  }
+}
+
+impl<R: Rules> Eq for Hand<R> {}
+
 impl<R: Rules> PartialOrd for Hand<R> {
   fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-    Some(self.cards.cmp(&other.cards))
+    Some(self.cmp(other))
   }
 }
 
 impl<R: Rules> Ord for Hand<R> {
   fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    self
-      .kind()
-      .cmp(&other.kind())
-      .then_with(|| self.cards.cmp(&other.cards))
+    self.key.cmp(&other.key)
+  }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct StandardRules;
+
+impl Rules for StandardRules {
+  type Kind = HandKind;
+
+  fn rank(card: Card) -> u8 {
+    card.face_value()
+  }
+
+  fn kind(cards: &[Card]) -> HandKind {
+    HandKind::of::<Self>(cards)
+  }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct JokerRules;
+
+impl Rules for JokerRules {
+  type Kind = HandKind;
+
+  fn rank(card: Card) -> u8 {
+    match card {
+      Card::JackOrJoker => 1,
+      card => card.face_value(),
+    }
+  }
+
+  fn is_wildcard(card: Card) -> bool {
+    card == Card::JackOrJoker
+  }
+
+  fn kind(cards: &[Card]) -> HandKind {
+    HandKind::of::<Self>(cards)
+  }
+}
+
+#[derive(Debug)]
+pub struct HandReport<R: Rules> {
+  pub hand: Hand<R>,
+  pub played_as: Hand<R>,
+  pub kind: R::Kind,
+  pub rank: u32,
+  pub bid: u32,
+  pub winnings: u32,
+}
+
+// Every hand of a game, from the weakest to the strongest.
+#[derive(Debug)]
+pub struct GameReport<R: Rules> {
+  pub hands: Vec<HandReport<R>>,
+}
+
+impl<R: Rules> GameReport<R> {
+  pub fn total_winnings(&self) -> u32 {
+    self.hands.iter().map(|report| report.winnings).sum()
+  }
+}
+
+impl<R: Rules> Display for GameReport<R> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let hand_width = R::HAND_SIZE.max(4);
+    let kinds = self
+      .hands
+      .iter()
+      .map(|report| format!("{:?}", report.kind))
+      .collect::<Vec<_>>();
+    let kind_width = kinds.iter().map(String::len).max().unwrap_or(0).max(4);
+
+    writeln!(
+      f,
+      "{:>4}  {:<hand_width$}  {:<hand_width$}  {:<kind_width$}  {:>5}  {:>8}",
+      "Rank", "Hand", "As", "Kind", "Bid", "Winnings"
+    )?;
+
+    for (report, kind) in self.hands.iter().zip(kinds) {
+      writeln!(
+        f,
+        "{:>4}  {:<hand_width$}  {:<hand_width$}  {:<kind_width$}  {:>5}  {:>8}",
+        report.rank,
+        report.hand.to_string(),
+        report.played_as.to_string(),
+        kind,
+        report.bid,
+        report.winnings
+      )?;
+    }
+
+    writeln!(f, "Total winnings: {}", self.total_winnings())
   }
 }
 
-fn play_game<R: Rules>(input: &str) -> u32 {
-  let mut data = input
+// A line of the input, which reads the same under any rules.
+struct Deal<'a> {
+  hand: &'a str,
+  cards: Vec<Card>,
+  bid: u32,
+}
+
+fn parse_deals(input: &str) -> Result<Vec<Deal>, ParseError> {
+  input
     .lines()
     .map(|line| {
-      let (hand, bid) = line.split_once(' ').unwrap();
-      (
-        hand.parse::<Hand<R>>().unwrap(),
-        bid.parse::<u32>().unwrap(),
-      )
+      let (hand, bid) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::expected(input, line, "a hand and a bid"))?;
+
+      Ok(Deal {
+        hand,
+        cards: parse_cards(hand).map_err(|err| err.within(input, hand))?,
+        bid: parse_number(input, bid)?,
+      })
     })
-    .collect::<Vec<_>>();
+    .collect()
+}
+
+fn parse_hands<R: Rules>(
+  input: &str,
+) -> Result<Vec<(Hand<R>, u32)>, ParseError> {
+  parse_deals(input)?
+    .into_iter()
+    .map(|deal| Ok((Hand::from_cards(input, deal.hand, deal.cards)?, deal.bid)))
+    .collect()
+}
+
+pub fn report<R: Rules>(input: &str) -> Result<GameReport<R>, ParseError> {
+  let mut data = parse_hands::<R>(input)?;
 
-  data.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+  data.sort_unstable_by_key(|(hand, _)| hand.key());
 
-  data
+  let hands = data
     .into_iter()
-    .enumerate()
-    .map(|(i, (_hand, bid))| (i as u32 + 1) * bid)
-    .sum()
-}
-
-pub fn part1(input: &str) -> u32 {
-  #[derive(Eq, PartialEq)]
-  struct Part1;
-
-  impl Rules for Part1 {
-    fn rank(card: &Card<Self>) -> usize {
-      match *card {
-        Card::Two => 2,
-        Card::Three => 3,
-        Card::Four => 4,
-        Card::Five => 5,
-        Card::Six => 6,
-        Card::Seven => 7,
-        Card::Eight => 8,
-        Card::Nine => 9,
-        Card::Ten => 10,
-        Card::JackOrJoker => 11,
-        Card::Queen => 12,
-        Card::King => 13,
-        Card::Ace(_) => 14,
-      }
-    }
+    .zip(1..)
+    .map(|((hand, bid), rank)| HandReport {
+      played_as: hand.played_as(),
+      kind: hand.kind(),
+      hand,
+      rank,
+      bid,
+      winnings: rank * bid,
+    })
+    .collect();
 
-    fn kind(hand: &Hand<Self>) -> HandKind {
-      let mut counts = [0u8; 0x14];
+  Ok(GameReport { hands })
+}
 
-      for card in &hand.cards {
-        counts[card.rank()] += 1;
-      }
+pub fn play_game<R: Rules>(input: &str) -> Result<u32, ParseError> {
+  Ok(report::<R>(input)?.total_winnings())
+}
 
-      HandKind::from_counts(&counts)
-    }
-  }
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+  play_game::<StandardRules>(input)
+}
 
-  play_game::<Part1>(input)
-}
-
-pub fn part2(input: &str) -> u32 {
-  #[derive(Eq, PartialEq)]
-  struct Part2;
-
-  impl Rules for Part2 {
-    fn rank(card: &Card<Self>) -> usize {
-      match *card {
-        Card::JackOrJoker => 1,
-        Card::Two => 2,
-        Card::Three => 3,
-        Card::Four => 4,
-        Card::Five => 5,
-        Card::Six => 6,
-        Card::Seven => 7,
-        Card::Eight => 8,
-        Card::Nine => 9,
-        Card::Ten => 10,
-        Card::Queen => 12,
-        Card::King => 13,
-        Card::Ace(_) => 14,
-      }
-    }
+pub fn part2(input: &str) -> Result<u32, ParseError> {
+  play_game::<JokerRules>(input)
+}
 
-    fn kind(hand: &Hand<Self>) -> HandKind {
-      let mut counts = [0u8; 0x14];
+pub struct Day07;
 
-      for card in &hand.cards {
-        counts[card.rank()] += 1;
-      }
+impl Solution for Day07 {
+  const DAY: u8 = 7;
 
-      let mut joker_count = counts[Card::<Self>::JackOrJoker.rank()];
-      counts[Card::<Self>::JackOrJoker.rank()] = 0;
+  type Part1 = u32;
+  type Part2 = u32;
 
-      while 0 < joker_count {
-        let max_count = counts.iter_mut().max().unwrap();
-        let assigned = (5 - *max_count).min(joker_count);
-        *max_count += assigned;
-        joker_count -= assigned;
-      }
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_deals(input).map(drop)
+  }
 
-      HandKind::from_counts(&counts)
-    }
+  fn part1(input: &str) -> Result<u32, ParseError> {
+    part1(input)
   }
 
-  play_game::<Part2>(input)
+  fn part2(input: &str) -> Result<u32, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -299,13 +535,163 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 6440);
-    assert_eq!(part1(INPUT), 247_823_654);
+    assert_eq!(part1(INPUT_TEST), Ok(6440));
+    assert_eq!(part1(INPUT), Ok(247_823_654));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 5905);
-    assert_eq!(part2(INPUT), 245_461_700);
+    assert_eq!(part2(INPUT_TEST), Ok(5905));
+    assert_eq!(part2(INPUT), Ok(245_461_700));
+  }
+
+  #[test]
+  fn supports_house_rules() {
+    // Three-card hands where both jokers and twos are wild, and a run of
+    // consecutive cards beats everything but three of a kind.
+    #[derive(Eq, PartialEq, Debug, Clone)]
+    struct ShortStraights;
+
+    #[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Copy, Debug)]
+    enum ShortKind {
+      Standard(HandKind),
+      Straight,
+      Triple,
+    }
+
+    impl From<ShortKind> for u8 {
+      fn from(kind: ShortKind) -> Self {
+        match kind {
+          ShortKind::Standard(kind) => kind.into(),
+          ShortKind::Straight => 7,
+          ShortKind::Triple => 8,
+        }
+      }
+    }
+
+    impl Rules for ShortStraights {
+      const HAND_SIZE: usize = 3;
+
+      type Kind = ShortKind;
+
+      fn rank(card: Card) -> u8 {
+        card.face_value()
+      }
+
+      fn is_wildcard(card: Card) -> bool {
+        matches!(card, Card::Two | Card::JackOrJoker)
+      }
+
+      fn kind(cards: &[Card]) -> ShortKind {
+        let mut values = cards
+          .iter()
+          .map(|card| card.face_value())
+          .collect::<Vec<_>>();
+        values.sort_unstable();
+
+        match HandKind::of::<Self>(cards) {
+          HandKind::ThreeOfAKind | HandKind::FiveOfAKind => ShortKind::Triple,
+          _ if values.windows(2).all(|pair| pair[0] + 1 == pair[1]) => {
+            ShortKind::Straight
+          }
+          kind => ShortKind::Standard(kind),
+        }
+      }
+    }
+
+    let hand = |s: &str| s.parse::<Hand<ShortStraights>>().unwrap();
+
+    assert_eq!(hand("KQA").kind(), ShortKind::Straight);
+    assert_eq!(hand("KQJ").kind(), ShortKind::Straight);
+    assert_eq!(hand("KQJ").played_as().kind(), ShortKind::Straight);
+    assert_eq!(hand("KQJ").wildcard_substitute(), Some(Card::Ace));
+    assert_eq!(hand("2J9").kind(), ShortKind::Triple);
+    assert_eq!(hand("2K9").kind(), ShortKind::Standard(HandKind::OnePair));
+    assert!(hand("345") > hand("AA3"));
+    assert!(hand("TTT") > hand("QKA"));
+
+    assert_eq!(play_game::<ShortStraights>("345 10\nAA3 1\n"), Ok(21));
+    assert!("32T3K".parse::<Hand<ShortStraights>>().is_err());
+
+    assert_eq!(HandKind::from_counts(&[3, 2, 2], 0), HandKind::FullHouse);
+    assert_eq!(HandKind::from_counts(&[2, 1], 2), HandKind::FourOfAKind);
+    assert_eq!(HandKind::from_counts(&[], 7), HandKind::FiveOfAKind);
+  }
+
+  #[test]
+  fn packs_sort_keys() {
+    let hand = "32T3K".parse::<Hand<StandardRules>>().unwrap();
+    assert_eq!(hand.key(), 0x1_32a3d);
+
+    let hand = "KTJJT".parse::<Hand<JokerRules>>().unwrap();
+    assert_eq!(hand.key(), 0x5_da11a);
+    assert!(hand > "KK677".parse().unwrap());
+  }
+
+  #[test]
+  #[should_panic(expected = "exceeds 4 bits")]
+  fn rejects_wide_ranks() {
+    #[derive(Eq, PartialEq, Debug, Clone)]
+    struct WideRanks;
+
+    impl Rules for WideRanks {
+      type Kind = HandKind;
+
+      fn rank(card: Card) -> u8 {
+        card.face_value() * 2
+      }
+
+      fn kind(cards: &[Card]) -> HandKind {
+        HandKind::of::<Self>(cards)
+      }
+    }
+
+    let _ = "AKQT9".parse::<Hand<WideRanks>>();
+  }
+
+  #[test]
+  #[should_panic(expected = "a hand must hold 5 cards")]
+  fn rejects_short_hands() {
+    Hand::<StandardRules>::new(vec![Card::Ace, Card::King]);
+  }
+
+  #[test]
+  fn reports_hand_rankings() {
+    let game = report::<JokerRules>(INPUT_TEST).unwrap();
+
+    let strongest = game.hands.last().unwrap();
+    assert_eq!(strongest.hand.to_string(), "KTJJT");
+    assert_eq!(strongest.played_as.to_string(), "KTTTT");
+    assert_eq!(strongest.kind, HandKind::FourOfAKind);
+    assert_eq!(strongest.hand.wildcard_substitute(), Some(Card::Ten));
+    assert_eq!((strongest.rank, strongest.winnings), (5, 5 * 220));
+
+    assert_eq!(
+      game.to_string(),
+      "Rank  Hand   As     Kind           Bid  Winnings\n   \
+          1  32T3K  32T3K  OnePair        765       765\n   \
+          2  KK677  KK677  TwoPairs        28        56\n   \
+          3  T55J5  T5555  FourOfAKind    684      2052\n   \
+          4  QQQJA  QQQQA  FourOfAKind    483      1932\n   \
+          5  KTJJT  KTTTT  FourOfAKind    220      1100\n\
+       Total winnings: 5905\n"
+    );
+
+    let game = report::<JokerRules>("JJJJJ 1\n").unwrap();
+    assert_eq!(game.hands[0].played_as.to_string(), "AAAAA");
+    assert_eq!(
+      report::<StandardRules>(INPUT_TEST).unwrap().hands[4].played_as,
+      "QQQJA".parse().unwrap()
+    );
+  }
+
+  #[test]
+  fn reports_invalid_cards() {
+    let err = part1("32T3K 765\nT55X5 684\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 4));
+    assert_eq!(err.kind, ParseErrorKind::InvalidChar('X'));
+
+    let err = part1("32T3 765\n").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 1));
   }
 }
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::{utils::ParseError, Solution};
+
 #[derive(Debug)]
 enum Step {
   Left,
@@ -11,32 +13,49 @@ struct Network<'a> {
   nodes: HashMap<&'a str, [&'a str; 2]>,
 }
 
-impl<'a> From<&'a str> for Network<'a> {
-  fn from(input: &'a str) -> Self {
-    let mut lines = input.lines();
+impl<'a> TryFrom<&'a str> for Network<'a> {
+  type Error = ParseError;
 
-    let steps = lines
-      .next()
-      .unwrap()
-      .chars()
-      .filter_map(|c| match c {
-        'L' => Some(Step::Left),
-        'R' => Some(Step::Right),
-        _ => None,
+  fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+    let mut lines = input.lines();
+    let steps_line = lines.next().unwrap_or(&input[input.len()..]);
+
+    if steps_line.is_empty() {
+      return Err(ParseError::expected(input, steps_line, "`L` or `R` steps"));
+    }
+
+    let steps = steps_line
+      .char_indices()
+      .map(|(i, c)| match c {
+        'L' => Ok(Step::Left),
+        'R' => Ok(Step::Right),
+        _ => Err(ParseError::invalid_char(input, &input[i..])),
       })
-      .collect();
+      .collect::<Result<_, _>>()?;
 
-    lines.next().unwrap();
+    let mut nodes = HashMap::new();
 
-    let nodes = lines
-      .filter_map(|line| {
-        let (from, rest) = line.split_once(" = ")?;
-        let &(left, right) = &rest[1..rest.len() - 1].split_once(", ")?;
-        Some((from, [left, right]))
-      })
-      .collect();
+    for line in lines.filter(|line| !line.is_empty()) {
+      let (from, rest) = line
+        .split_once(" = ")
+        .ok_or_else(|| ParseError::expected(input, line, "`NODE = (L, R)`"))?;
+
+      let (left, right) = rest
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .and_then(|rest| rest.split_once(", "))
+        .ok_or_else(|| ParseError::expected(input, rest, "`(L, R)`"))?;
+
+      nodes.insert(from, [left, right]);
+    }
+
+    for &node in nodes.values().flatten() {
+      if !nodes.contains_key(node) {
+        return Err(ParseError::expected(input, node, "a defined node"));
+      }
+    }
 
-    Network { steps, nodes }
+    Ok(Network { steps, nodes })
   }
 }
 
@@ -56,34 +75,85 @@ impl Network<'_> {
     })
   }
 
+  // Every (node, step) state has been visited once the walk is this long,
+  // so a target that wasn't reached by then never will be.
   fn search(
     &self,
     from_node: &str,
     to_node: impl FnMut(&str) -> bool,
-  ) -> usize {
-    self.steps(from_node).position(to_node).unwrap() + 1
+  ) -> Option<usize> {
+    self
+      .steps(from_node)
+      .take(self.steps.len() * self.nodes.len())
+      .position(to_node)
+      .map(|steps| steps + 1)
   }
 }
 
-pub fn part1(input: &str) -> usize {
-  let network = Network::from(input);
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+  let network = Network::try_from(input)?;
+  let end = &input[input.len()..];
 
-  network.search("AAA", |node| node == "ZZZ")
+  for node in ["AAA", "ZZZ"] {
+    if !network.nodes.contains_key(node) {
+      return Err(ParseError::expected(input, end, "nodes `AAA` and `ZZZ`"));
+    }
+  }
+
+  network
+    .search("AAA", |node| node == "ZZZ")
+    .ok_or_else(|| ParseError::expected(input, end, "a route to `ZZZ`"))
 }
 
-pub fn part2(input: &str) -> usize {
-  let network = Network::from(input);
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+  let network = Network::try_from(input)?;
+  let end = &input[input.len()..];
 
-  network
+  let starts = network
     .nodes()
     .filter(|node| node.ends_with('A'))
-    .map(|node| network.search(node, |node| node.ends_with('Z')))
-    .fold(1, num::integer::lcm)
+    .collect::<Vec<_>>();
+
+  if starts.is_empty() {
+    return Err(ParseError::expected(input, end, "a node ending in `A`"));
+  }
+
+  starts.into_iter().try_fold(1, |steps, node| {
+    let route = network
+      .search(node, |node| node.ends_with('Z'))
+      .ok_or_else(|| {
+        ParseError::expected(input, end, "a route to a node ending in `Z`")
+      })?;
+
+    Ok(num::integer::lcm(steps, route))
+  })
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+  const DAY: u8 = 8;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    Network::try_from(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::utils::ParseErrorKind::Expected;
 
   const INPUT: &str = include_str!("input.txt");
   const INPUT_TEST_0: &str = include_str!("input_test_0.txt");
@@ -92,14 +162,33 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST_0), 2);
-    assert_eq!(part1(INPUT_TEST_1), 6);
-    assert_eq!(part1(INPUT), 23_147);
+    assert_eq!(part1(INPUT_TEST_0), Ok(2));
+    assert_eq!(part1(INPUT_TEST_1), Ok(6));
+    assert_eq!(part1(INPUT), Ok(23_147));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST_2), 6);
-    assert_eq!(part2(INPUT), 22_289_513_667_691);
+    assert_eq!(part2(INPUT_TEST_2), Ok(6));
+    assert_eq!(part2(INPUT), Ok(22_289_513_667_691));
+  }
+
+  #[test]
+  fn reports_missing_nodes_and_routes() {
+    let error = |input| part1(input).unwrap_err().kind;
+
+    assert_eq!(error("\nAAA = (AAA, AAA)\n"), Expected("`L` or `R` steps"));
+    assert_eq!(
+      error("LR\n\nBBB = (BBB, BBB)\n"),
+      Expected("nodes `AAA` and `ZZZ`")
+    );
+    assert_eq!(
+      error("LR\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n"),
+      Expected("a route to `ZZZ`")
+    );
+    assert_eq!(
+      part2("LR\n\nBBB = (BBB, BBB)\n").unwrap_err().kind,
+      Expected("a node ending in `A`")
+    );
   }
 }
@@ -1,68 +1,139 @@
 use itertools::Itertools;
 
-fn layers(input: &str) -> impl Iterator<Item = Vec<Vec<i32>>> + '_ {
-  input.lines().map(|line| {
-    let nums: Vec<i32> = line
-      .split_whitespace()
-      .filter_map(|n| n.parse().ok())
-      .collect();
-
-    let mut layers = vec![nums];
-
-    while let Some(last_layer) =
-      layers.last().filter(|layer| layer.iter().any(|n| *n != 0))
-    {
-      layers.push(
-        last_layer
-          .iter()
-          .tuple_windows()
-          .map(|(a, b)| b - a)
-          .collect(),
-      );
-    }
-
-    layers
-  })
-}
+use crate::{
+  utils::{parse_number, ParseError},
+  Solution,
+};
+
+fn parse_histories(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+  input
+    .lines()
+    .map(|line| {
+      let history = line
+        .split_whitespace()
+        .map(|n| parse_number(input, n))
+        .collect::<Result<Vec<_>, _>>()?;
+
+      if history.len() < 2 {
+        return Err(ParseError::expected(input, line, "at least two values"));
+      }
 
-pub fn part1(input: &str) -> i32 {
-  layers(input)
-    .map(|layers| {
-      layers
-        .iter()
-        .rev()
-        .fold(0, |prev, layer| layer.last().unwrap() + prev)
+      Ok(history)
     })
-    .sum()
+    .collect()
 }
 
-pub fn part2(input: &str) -> i32 {
-  layers(input)
-    .map(|layers| {
-      layers
-        .iter()
-        .rev()
-        .fold(0, |prev, layer| layer.first().unwrap() - prev)
+fn layers(input: &str) -> Result<Vec<Vec<Vec<i32>>>, ParseError> {
+  let histories = parse_histories(input)?;
+
+  input
+    .lines()
+    .zip(histories)
+    .map(|(line, history)| {
+      let mut layers = vec![history];
+
+      while let Some(last_layer) =
+        layers.last().filter(|layer| layer.iter().any(|n| *n != 0))
+      {
+        layers.push(
+          last_layer
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| b - a)
+            .collect(),
+        );
+      }
+
+      // The differences ran out before reaching zero, so there is no
+      // value left to extrapolate from.
+      if layers.last().is_some_and(Vec::is_empty) {
+        return Err(ParseError::expected(
+          input,
+          line,
+          "differences that reach zero",
+        ));
+      }
+
+      Ok(layers)
     })
-    .sum()
+    .collect()
+}
+
+pub fn part1(input: &str) -> Result<i32, ParseError> {
+  Ok(
+    layers(input)?
+      .iter()
+      .map(|layers| {
+        layers
+          .iter()
+          .rev()
+          .fold(0, |prev, layer| layer.last().unwrap() + prev)
+      })
+      .sum(),
+  )
+}
+
+pub fn part2(input: &str) -> Result<i32, ParseError> {
+  Ok(
+    layers(input)?
+      .iter()
+      .map(|layers| {
+        layers
+          .iter()
+          .rev()
+          .fold(0, |prev, layer| layer.first().unwrap() - prev)
+      })
+      .sum(),
+  )
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+  const DAY: u8 = 9;
+
+  type Part1 = i32;
+  type Part2 = i32;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_histories(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<i32, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<i32, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::utils::ParseErrorKind::Expected;
 
   const INPUT_TEST: &str = include_str!("input_test.txt");
   const INPUT: &str = include_str!("input.txt");
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 114);
-    assert_eq!(part1(INPUT), 1_972_648_895);
+    assert_eq!(part1(INPUT_TEST), Ok(114));
+    assert_eq!(part1(INPUT), Ok(1_972_648_895));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 2);
-    assert_eq!(part2(INPUT), 919);
+    assert_eq!(part2(INPUT_TEST), Ok(2));
+    assert_eq!(part2(INPUT), Ok(919));
+  }
+
+  #[test]
+  fn reports_short_histories() {
+    let error = |input| part1(input).unwrap_err().kind;
+
+    assert_eq!(error("1"), Expected("at least two values"));
+    assert_eq!(error("0 3 6\n\n"), Expected("at least two values"));
+    assert_eq!(error("1 2 4 8"), Expected("differences that reach zero"));
   }
 }
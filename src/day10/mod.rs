@@ -1,56 +1,16 @@
-use std::{
-  collections::HashMap,
-  iter,
-  ops::{AddAssign, SubAssign},
-  str::FromStr,
-};
+use std::{collections::HashMap, iter, str::FromStr};
 
 use itertools::Itertools;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Point<T, const N: usize>(pub [T; N]);
-
-impl<T> AddAssign<Direction> for Point<T, 2>
-where
-  T: AddAssign + SubAssign + num::One,
-{
-  fn add_assign(&mut self, dir: Direction) {
-    let Self([x, y]) = self;
-    match dir {
-      Direction::North => *y -= num::one(),
-      Direction::South => *y += num::one(),
-      Direction::East => *x += num::one(),
-      Direction::West => *x -= num::one(),
-    }
-  }
-}
-
-type Position = Point<i32, 2>;
-
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-  North,
-  South,
-  East,
-  West,
-}
-
-impl Direction {
-  fn variants() -> impl Iterator<Item = Self> {
-    [Self::North, Self::South, Self::East, Self::West].into_iter()
-  }
+use crate::{
+  utils::{
+    geom::{Direction, Point},
+    ParseError,
+  },
+  Solution,
+};
 
-  fn rotate(&mut self, rot: isize) {
-    for _ in 0..rot.rem_euclid(4) {
-      *self = match self {
-        Direction::North => Direction::East,
-        Direction::South => Direction::West,
-        Direction::East => Direction::South,
-        Direction::West => Direction::North,
-      }
-    }
-  }
-}
+type Position = Point<i32>;
 
 #[derive(Debug, Clone, Copy)]
 enum Tile {
@@ -67,15 +27,15 @@ enum Tile {
 struct Tiles(HashMap<Position, Tile>);
 
 impl FromStr for Tiles {
-  type Err = std::convert::Infallible;
+  type Err = ParseError;
 
   fn from_str(input: &str) -> Result<Self, Self::Err> {
     let mut tiles = HashMap::new();
 
     for (y, line) in input.lines().enumerate() {
-      for (x, c) in line.chars().enumerate() {
+      for (x, (i, c)) in line.char_indices().enumerate() {
         tiles.insert(
-          Point([x as i32, y as i32]),
+          Point::new(x as i32, y as i32),
           match c {
             '|' => Tile::Vertical,
             '-' => Tile::Horizontal,
@@ -84,13 +44,21 @@ impl FromStr for Tiles {
             '7' => Tile::SouthWest,
             'F' => Tile::SouthEast,
             'S' => Tile::Start,
-            _ => continue,
+            '.' => continue,
+            _ => return Err(ParseError::invalid_char(input, &line[i..])),
           },
         );
       }
     }
 
-    Ok(Self(tiles))
+    let tiles = Self(tiles);
+
+    if tiles.start_pos().is_none() {
+      let end = &input[input.len()..];
+      return Err(ParseError::expected(input, end, "a start tile `S`"));
+    }
+
+    Ok(tiles)
   }
 }
 
@@ -109,20 +77,20 @@ impl Tiles {
   ) -> impl Iterator<Item = Position> + '_ {
     iter::from_fn(move || {
       pos += dir;
-      dir.rotate(match (dir, *self.0.get(&pos)?) {
+      dir = dir.rotate(match (dir, *self.0.get(&pos)?) {
         (_, Tile::Start) => 0,
-        (Direction::North, Tile::Vertical) => 0,
-        (Direction::North, Tile::SouthWest) => -1,
-        (Direction::North, Tile::SouthEast) => 1,
-        (Direction::South, Tile::Vertical) => 0,
-        (Direction::South, Tile::NorthEast) => -1,
-        (Direction::South, Tile::NorthWest) => 1,
-        (Direction::East, Tile::Horizontal) => 0,
-        (Direction::East, Tile::NorthWest) => -1,
-        (Direction::East, Tile::SouthWest) => 1,
-        (Direction::West, Tile::Horizontal) => 0,
-        (Direction::West, Tile::SouthEast) => -1,
-        (Direction::West, Tile::NorthEast) => 1,
+        (Direction::Up, Tile::Vertical) => 0,
+        (Direction::Up, Tile::SouthWest) => -1,
+        (Direction::Up, Tile::SouthEast) => 1,
+        (Direction::Down, Tile::Vertical) => 0,
+        (Direction::Down, Tile::NorthEast) => -1,
+        (Direction::Down, Tile::NorthWest) => 1,
+        (Direction::Right, Tile::Horizontal) => 0,
+        (Direction::Right, Tile::NorthWest) => -1,
+        (Direction::Right, Tile::SouthWest) => 1,
+        (Direction::Left, Tile::Horizontal) => 0,
+        (Direction::Left, Tile::SouthEast) => -1,
+        (Direction::Left, Tile::NorthEast) => 1,
         _ => return None,
       });
       Some(pos)
@@ -130,32 +98,43 @@ impl Tiles {
   }
 }
 
-pub fn part1(input: &str) -> usize {
-  let tiles: Tiles = input.parse().unwrap();
-
-  let start_pos = tiles.start_pos().unwrap();
-
-  Direction::variants()
-    .filter_map(|start_dir| {
-      let (steps, _) = tiles.steps(start_pos, start_dir).enumerate().last()?;
-      Some(steps / 2 + 1)
-    })
-    .max()
-    .unwrap()
+impl Tiles {
+  // The direction to leave the start tile in to follow the longest pipe,
+  // and the number of steps it takes before the pipe runs out.
+  fn longest_loop(
+    &self,
+    input: &str,
+  ) -> Result<(Position, Direction, usize), ParseError> {
+    let start_pos = self.start_pos().unwrap();
+
+    Direction::variants()
+      .into_iter()
+      .filter_map(|start_dir| {
+        let (steps, _) = self.steps(start_pos, start_dir).enumerate().last()?;
+        Some((start_pos, start_dir, steps))
+      })
+      .max_by_key(|&(_, _, steps)| steps)
+      .ok_or_else(|| {
+        let start = input.find('S').unwrap();
+        ParseError::expected(
+          input,
+          &input[start..start + 1],
+          "a start tile connected to a pipe",
+        )
+      })
+  }
 }
 
-pub fn part2(input: &str) -> i32 {
-  let tiles: Tiles = input.parse().unwrap();
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+  let tiles: Tiles = input.parse()?;
+  let (_, _, steps) = tiles.longest_loop(input)?;
 
-  let start_pos = tiles.start_pos().unwrap();
+  Ok(steps / 2 + 1)
+}
 
-  let (_, start_dir) = Direction::variants()
-    .filter_map(|start_dir| {
-      let (steps, _) = tiles.steps(start_pos, start_dir).enumerate().last()?;
-      Some((steps, start_dir))
-    })
-    .max_by_key(|&(steps, _)| steps)
-    .unwrap();
+pub fn part2(input: &str) -> Result<i32, ParseError> {
+  let tiles: Tiles = input.parse()?;
+  let (start_pos, start_dir, _) = tiles.longest_loop(input)?;
 
   let path = iter::once(start_pos)
     .chain(tiles.steps(start_pos, start_dir))
@@ -164,11 +143,32 @@ pub fn part2(input: &str) -> i32 {
   let twice_area = path
     .iter()
     .tuple_windows()
-    .map(|(Point([x0, y0]), Point([x1, y1]))| (x1 + x0) * (y1 - y0))
+    .map(|(p0, p1)| (p1.x + p0.x) * (p1.y - p0.y))
     .sum::<i32>()
     .abs();
 
-  twice_area / 2 - (path.len() as i32 / 2 - 1)
+  Ok(twice_area / 2 - (path.len() as i32 / 2 - 1))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+  const DAY: u8 = 10;
+
+  type Part1 = usize;
+  type Part2 = i32;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    input.parse::<Tiles>().map(drop)
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<i32, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -184,16 +184,23 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST_0), 4);
-    assert_eq!(part1(INPUT_TEST_1), 8);
-    assert_eq!(part1(INPUT), 6690);
+    assert_eq!(part1(INPUT_TEST_0), Ok(4));
+    assert_eq!(part1(INPUT_TEST_1), Ok(8));
+    assert_eq!(part1(INPUT), Ok(6690));
+  }
+
+  #[test]
+  fn reports_missing_loop() {
+    let err = part1("S.\n..\n").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 1));
+    assert!(part2("..\n.S\n").is_err());
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST_2), 4);
-    assert_eq!(part2(INPUT_TEST_3), 8);
-    assert_eq!(part2(INPUT_TEST_4), 10);
-    assert_eq!(part2(INPUT), 525);
+    assert_eq!(part2(INPUT_TEST_2), Ok(4));
+    assert_eq!(part2(INPUT_TEST_3), Ok(8));
+    assert_eq!(part2(INPUT_TEST_4), Ok(10));
+    assert_eq!(part2(INPUT), Ok(525));
   }
 }
@@ -1,19 +1,27 @@
-pub fn solve(input: &str, multiplier: i64) -> i64 {
-  let mut galaxies = Vec::new();
+use crate::{utils::ParseError, Solution};
 
-  let mut y_max = 0;
-  let mut x_max = 0;
+fn parse_galaxies(input: &str) -> Result<Vec<[i64; 2]>, ParseError> {
+  let mut galaxies = Vec::new();
 
   for (y, line) in input.lines().enumerate() {
-    y_max = y_max.max(y);
-    for (x, c) in line.chars().enumerate() {
-      x_max = x_max.max(x);
-      if c == '#' {
-        galaxies.push([x as i64, y as i64]);
+    for (x, (i, c)) in line.char_indices().enumerate() {
+      match c {
+        '#' => galaxies.push([x as i64, y as i64]),
+        '.' => {}
+        _ => return Err(ParseError::invalid_char(input, &line[i..])),
       }
     }
   }
 
+  Ok(galaxies)
+}
+
+pub fn solve(input: &str, multiplier: i64) -> Result<i64, ParseError> {
+  let galaxies = parse_galaxies(input)?;
+
+  let x_max = galaxies.iter().map(|&[x, _]| x as usize).max().unwrap_or(0);
+  let y_max = galaxies.iter().map(|&[_, y]| y as usize).max().unwrap_or(0);
+
   let mut empty_cols = vec![true; x_max + 1];
   let mut empty_rows = vec![true; y_max + 1];
 
@@ -50,7 +58,36 @@ pub fn solve(input: &str, multiplier: i64) -> i64 {
     }
   }
 
-  sum
+  Ok(sum)
+}
+
+pub fn part1(input: &str) -> Result<i64, ParseError> {
+  solve(input, 2)
+}
+
+pub fn part2(input: &str) -> Result<i64, ParseError> {
+  solve(input, 1_000_000)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+  const DAY: u8 = 11;
+
+  type Part1 = i64;
+  type Part2 = i64;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_galaxies(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<i64, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<i64, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -62,14 +99,20 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(solve(INPUT_TEST, 2), 374);
-    assert_eq!(solve(INPUT, 2), 9_742_154);
+    assert_eq!(solve(INPUT_TEST, 2), Ok(374));
+    assert_eq!(part1(INPUT), Ok(9_742_154));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(solve(INPUT_TEST, 10), 1030);
-    assert_eq!(solve(INPUT_TEST, 100), 8410);
-    assert_eq!(solve(INPUT, 1_000_000), 411_142_919_886);
+    assert_eq!(solve(INPUT_TEST, 10), Ok(1030));
+    assert_eq!(solve(INPUT_TEST, 100), Ok(8410));
+    assert_eq!(part2(INPUT), Ok(411_142_919_886));
+  }
+
+  #[test]
+  fn reports_invalid_chars() {
+    let err = part1("#..\n.x#\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 2));
   }
 }
@@ -1,5 +1,10 @@
 use std::collections::HashMap;
 
+use crate::{
+  utils::{parse_number, ParseError},
+  Solution,
+};
+
 fn count_arrangements<'a, 'b>(
   cache: &mut HashMap<(&'a str, &'b [usize]), usize>,
   springs: &'a str,
@@ -44,35 +49,70 @@ fn count_arrangements<'a, 'b>(
   sum
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = (&str, Box<[usize]>)> {
+fn parse_input(
+  input: &str,
+) -> impl Iterator<Item = Result<(&str, Box<[usize]>), ParseError>> {
   input.lines().map(|line| {
-    let (springs, groups) = line.split_once(' ').unwrap();
-    let groups: Vec<usize> =
-      groups.split(',').map(|g| g.parse().unwrap()).collect();
-    (springs, groups.into())
+    let (springs, groups) = line
+      .split_once(' ')
+      .ok_or_else(|| ParseError::expected(input, line, "springs and groups"))?;
+
+    if let Some(i) = springs.find(|c| !matches!(c, '.' | '#' | '?')) {
+      return Err(ParseError::invalid_char(input, &springs[i..]));
+    }
+
+    let groups = groups
+      .split(',')
+      .map(|g| parse_number(input, g))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((springs, groups.into()))
   })
 }
 
-pub fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> Result<usize, ParseError> {
   parse_input(input)
-    .map(|(springs, groups)| {
-      count_arrangements(&mut HashMap::new(), springs, &groups)
+    .map(|line| {
+      let (springs, groups) = line?;
+      Ok(count_arrangements(&mut HashMap::new(), springs, &groups))
     })
     .sum()
 }
 
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> Result<usize, ParseError> {
   parse_input(input)
-    .map(|(springs, groups)| {
-      count_arrangements(
+    .map(|line| {
+      let (springs, groups) = line?;
+      Ok(count_arrangements(
         &mut HashMap::new(),
         &[springs; 5].join("?"),
         &groups.repeat(5),
-      )
+      ))
     })
     .sum()
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+  const DAY: u8 = 12;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_input(input).try_for_each(|line| line.map(drop))
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -82,13 +122,13 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 21);
-    assert_eq!(part1(INPUT), 7771);
+    assert_eq!(part1(INPUT_TEST), Ok(21));
+    assert_eq!(part1(INPUT), Ok(7771));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 525_152);
-    assert_eq!(part2(INPUT), 10_861_030_975_833);
+    assert_eq!(part2(INPUT_TEST), Ok(525_152));
+    assert_eq!(part2(INPUT), Ok(10_861_030_975_833));
   }
 }
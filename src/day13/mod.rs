@@ -1,119 +1,110 @@
-use std::{array, collections::HashSet, ops::RangeInclusive};
-
-fn ranges<'a, T: Copy + Ord + 'a, const N: usize>(
-  items: impl Iterator<Item = &'a [T; N]>,
-) -> Option<[RangeInclusive<T>; N]> {
-  items.fold(None, |ranges, item| {
-    Some(if let Some(ranges) = ranges {
-      array::from_fn(|i| {
-        item[i].min(*ranges[i].start())..=item[i].max(*ranges[i].end())
-      })
-    } else {
-      array::from_fn(|i| item[i]..=item[i])
-    })
-  })
+use crate::{
+  utils::{Grid, ParseError},
+  Solution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ground {
+  Ash,
+  Rock,
 }
 
-fn maps(input: &str) -> impl Iterator<Item = HashSet<[usize; 2]>> + '_ {
-  input.split("\n\n").map(|pattern| {
-    let mut map = HashSet::new();
-
-    for (y, line) in pattern.lines().enumerate() {
-      for (x, c) in line.chars().enumerate() {
-        if c == '#' {
-          map.insert([x, y]);
-        }
-      }
+#[derive(Debug)]
+enum ParseItemError {
+  InvalidChar(char),
+}
+
+impl TryFrom<char> for Ground {
+  type Error = ParseItemError;
+
+  fn try_from(c: char) -> Result<Self, Self::Error> {
+    match c {
+      '.' => Ok(Self::Ash),
+      '#' => Ok(Self::Rock),
+      c => Err(ParseItemError::InvalidChar(c)),
     }
+  }
+}
 
-    map
+impl Grid<Ground> {
+  fn horizontal_reflection(&self, smudges: usize) -> Option<isize> {
+    (1..self.rows()).find(|&y| {
+      (0..y)
+        .rev()
+        .zip(y..self.rows())
+        .map(|(y0, y1)| {
+          self
+            .row(y0)
+            .zip(self.row(y1))
+            .filter(|(a, b)| a != b)
+            .count()
+        })
+        .sum::<usize>()
+        == smudges
+    })
+  }
+
+  fn summarize(&self, smudges: usize) -> Option<usize> {
+    if let Some(x) = self.transpose().horizontal_reflection(smudges) {
+      return Some(x as usize);
+    }
+
+    self
+      .horizontal_reflection(smudges)
+      .map(|y| y as usize * 100)
+  }
+}
+
+fn maps(
+  input: &str,
+) -> impl Iterator<Item = Result<(&str, Grid<Ground>), ParseError>> + '_ {
+  input.split("\n\n").map(|pattern| {
+    let map = pattern
+      .parse()
+      .map_err(|err| ParseError::from(err).within(input, pattern))?;
+    Ok((pattern, map))
   })
 }
 
-pub fn part1(input: &str) -> usize {
+fn summarize(input: &str, smudges: usize) -> Result<usize, ParseError> {
   maps(input)
     .map(|map| {
-      let [x_range, y_range] = ranges(map.iter()).unwrap();
-
-      if let Some(vertical) =
-        (x_range.start() + 1..=*x_range.end()).find(|&x| {
-          y_range.clone().all(|y| {
-            (*x_range.start()..x)
-              .rev()
-              .zip(x..=*x_range.end())
-              .all(|(x0, x1)| map.contains(&[x0, y]) == map.contains(&[x1, y]))
-          })
-        })
-      {
-        return vertical;
-      }
-
-      if let Some(horizontal) =
-        (y_range.start() + 1..=*y_range.end()).find(|&y| {
-          x_range.clone().all(|x| {
-            (*y_range.start()..y)
-              .rev()
-              .zip(y..=*y_range.end())
-              .all(|(y0, y1)| map.contains(&[x, y0]) == map.contains(&[x, y1]))
-          })
-        })
-      {
-        return horizontal * 100;
-      }
+      let (pattern, map) = map?;
 
-      panic!("No line found!");
+      map.summarize(smudges).ok_or_else(|| {
+        ParseError::expected(input, pattern, "a line of reflection")
+      })
     })
     .sum()
 }
 
-pub fn part2(input: &str) -> usize {
-  maps(input)
-    .map(|map| {
-      let [x_range, y_range] = ranges(map.iter()).unwrap();
-
-      if let Some(vertical) =
-        (x_range.start() + 1..=*x_range.end()).find(|&x| {
-          y_range
-            .clone()
-            .map(|y| {
-              (*x_range.start()..x)
-                .rev()
-                .zip(x..=*x_range.end())
-                .filter(|&(x0, x1)| {
-                  map.contains(&[x0, y]) != map.contains(&[x1, y])
-                })
-                .count()
-            })
-            .sum::<usize>()
-            == 1
-        })
-      {
-        return vertical;
-      }
-
-      if let Some(horizontal) =
-        (y_range.start() + 1..=*y_range.end()).find(|&y| {
-          x_range
-            .clone()
-            .map(|x| {
-              (*y_range.start()..y)
-                .rev()
-                .zip(y..=*y_range.end())
-                .filter(|&(y0, y1)| {
-                  map.contains(&[x, y0]) != map.contains(&[x, y1])
-                })
-                .count()
-            })
-            .sum::<usize>()
-            == 1
-        })
-      {
-        return horizontal * 100;
-      }
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+  summarize(input, 0)
+}
 
-      panic!("No line found!");
-    })
-    .sum()
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+  summarize(input, 1)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+  const DAY: u8 = 13;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    maps(input).try_for_each(|map| map.map(drop))
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -125,13 +116,20 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 405);
-    assert_eq!(part1(INPUT), 34_911);
+    assert_eq!(part1(INPUT_TEST), Ok(405));
+    assert_eq!(part1(INPUT), Ok(34_911));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 400);
-    assert_eq!(part2(INPUT), 33_183);
+    assert_eq!(part2(INPUT_TEST), Ok(400));
+    assert_eq!(part2(INPUT), Ok(33_183));
+  }
+
+  #[test]
+  fn reports_missing_reflection() {
+    let err = part1("#.\n..\n\n##\n##\n").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 1));
+    assert_eq!(err.snippet, "#.\n..");
   }
 }
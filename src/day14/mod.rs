@@ -3,221 +3,101 @@ use std::{
     hash_map::{self, DefaultHasher},
     HashMap,
   },
-  fmt::Display,
+  fmt::{Display, Write},
   hash::{Hash, Hasher},
-  str::FromStr,
 };
 
-#[derive(PartialEq, Eq, Hash)]
+use crate::{
+  utils::{Grid, ParseError},
+  Solution,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 enum Entry {
   RoundRock,
   CubeRock,
   Empty,
 }
 
-#[derive(PartialEq, Eq, Hash)]
-struct ParabolicReflectorDish {
-  rows: usize,
-  entries: Vec<Entry>,
-}
-
 #[derive(Debug)]
-enum ParabolicReflectorDishParseError {
+enum ParseItemError {
   InvalidChar(char),
 }
 
-impl FromStr for ParabolicReflectorDish {
-  type Err = ParabolicReflectorDishParseError;
-
-  fn from_str(s: &str) -> Result<Self, Self::Err> {
-    let mut rows = 0;
-    let mut entries = Vec::new();
-
-    for line in s.lines() {
-      rows += 1;
-      for c in line.chars() {
-        entries.push(match c {
-          'O' => Entry::RoundRock,
-          '#' => Entry::CubeRock,
-          '.' => Entry::Empty,
-          c => {
-            return Err(ParabolicReflectorDishParseError::InvalidChar(c));
-          }
-        });
-      }
-    }
-
-    Ok(Self { rows, entries })
-  }
-}
+impl TryFrom<char> for Entry {
+  type Error = ParseItemError;
 
-impl ParabolicReflectorDish {
-  fn bounds(&self) -> [[usize; 2]; 2] {
-    let y_min = 0;
-    let y_max = self.rows - 1;
-    let x_min = 0;
-    let x_max = (self.entries.len() / self.rows) - 1;
-    [[x_min, x_max], [y_min, y_max]]
+  fn try_from(c: char) -> Result<Self, Self::Error> {
+    match c {
+      'O' => Ok(Self::RoundRock),
+      '#' => Ok(Self::CubeRock),
+      '.' => Ok(Self::Empty),
+      c => Err(ParseItemError::InvalidChar(c)),
+    }
   }
 }
 
-impl Display for ParabolicReflectorDish {
+impl Display for Entry {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    let [[x_min, x_max], [y_min, y_max]] = self.bounds();
-
-    for y in y_min..=y_max {
-      for x in x_min..=x_max {
-        write!(
-          f,
-          "{}",
-          match self.entries[y * self.rows + x] {
-            Entry::CubeRock => '#',
-            Entry::RoundRock => 'O',
-            Entry::Empty => '.',
-          }
-        )?;
-      }
-      writeln!(f)?;
+    match *self {
+      Self::RoundRock => f.write_char('O'),
+      Self::CubeRock => f.write_char('#'),
+      Self::Empty => f.write_char('.'),
     }
-
-    Ok(())
   }
 }
 
-impl ParabolicReflectorDish {
+impl Grid<Entry> {
   fn tilt_north(&mut self) {
-    let [[x_min, x_max], [y_min, y_max]] = self.bounds();
-
-    for x in x_min..=x_max {
-      for y in y_min..=y_max {
-        let index = y * self.rows + x;
-        if let Entry::Empty = self.entries[index] {
-          for y_next in y..=y_max {
-            let index_next = y_next * self.rows + x;
-            match self.entries[index_next] {
-              Entry::Empty => {
-                continue;
-              }
-              Entry::CubeRock => {
-                break;
-              }
-              Entry::RoundRock => {
-                self.entries.swap(index, index_next);
-                break;
-              }
-            }
+    for x in 0..self.cols() {
+      let mut free_y = 0;
+
+      for y in 0..self.rows() {
+        match self[[x, y]] {
+          Entry::Empty => {}
+          Entry::CubeRock => {
+            free_y = y + 1;
           }
-        }
-      }
-    }
-  }
-
-  fn tilt_south(&mut self) {
-    let [[x_min, x_max], [y_min, y_max]] = self.bounds();
-
-    for x in x_min..=x_max {
-      for y in (y_min..=y_max).rev() {
-        let index = y * self.rows + x;
-        if let Entry::Empty = self.entries[index] {
-          for y_next in (y_min..=y).rev() {
-            let index_next = y_next * self.rows + x;
-            match self.entries[index_next] {
-              Entry::Empty => {
-                continue;
-              }
-              Entry::CubeRock => {
-                break;
-              }
-              Entry::RoundRock => {
-                self.entries.swap(index, index_next);
-                break;
-              }
-            }
+          Entry::RoundRock => {
+            self[[x, y]] = Entry::Empty;
+            self[[x, free_y]] = Entry::RoundRock;
+            free_y += 1;
           }
         }
       }
     }
   }
 
-  fn tilt_west(&mut self) {
-    let [[x_min, x_max], [y_min, y_max]] = self.bounds();
-
-    for y in y_min..=y_max {
-      for x in x_min..=x_max {
-        let index = y * self.rows + x;
-        if let Entry::Empty = self.entries[index] {
-          for x_next in x..=x_max {
-            let index_next = y * self.rows + x_next;
-            match self.entries[index_next] {
-              Entry::Empty => {
-                continue;
-              }
-              Entry::CubeRock => {
-                break;
-              }
-              Entry::RoundRock => {
-                self.entries.swap(index, index_next);
-                break;
-              }
-            }
-          }
-        }
-      }
-    }
-  }
-
-  fn tilt_east(&mut self) {
-    let [[x_min, x_max], [y_min, y_max]] = self.bounds();
-
-    for y in y_min..=y_max {
-      for x in (x_min..=x_max).rev() {
-        let index = y * self.rows + x;
-        if let Entry::Empty = self.entries[index] {
-          for x_next in (x_min..=x).rev() {
-            let index_next = y * self.rows + x_next;
-            match self.entries[index_next] {
-              Entry::Empty => {
-                continue;
-              }
-              Entry::CubeRock => {
-                break;
-              }
-              Entry::RoundRock => {
-                self.entries.swap(index, index_next);
-                break;
-              }
-            }
-          }
-        }
-      }
+  fn spin_cycle(&mut self) {
+    for _ in 0..4 {
+      self.tilt_north();
+      *self = self.rotate_cw();
     }
   }
 
   fn north_beams_load(&self) -> usize {
-    let [_, [_, y_max]] = self.bounds();
+    let rows = self.rows();
 
     self
-      .entries
       .iter()
-      .enumerate()
-      .filter_map(|(index, entry)| match entry {
-        Entry::RoundRock => Some(y_max - index / self.rows + 1),
+      .filter_map(|([_, y], entry)| match entry {
+        Entry::RoundRock => Some((rows - y) as usize),
         _ => None,
       })
       .sum()
   }
 }
 
-pub fn part1(input: &str) -> usize {
-  let mut dish: ParabolicReflectorDish = input.parse().unwrap();
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+  let mut dish: Grid<Entry> = input.parse()?;
 
   dish.tilt_north();
 
-  dish.north_beams_load()
+  Ok(dish.north_beams_load())
 }
 
-pub fn part2(input: &str) -> usize {
-  let mut dish: ParabolicReflectorDish = input.parse().unwrap();
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+  let mut dish: Grid<Entry> = input.parse()?;
 
   let mut dish_hashes = HashMap::new();
 
@@ -235,8 +115,9 @@ pub fn part2(input: &str) -> usize {
         let prev_n = entry.get();
         let cycle_start = prev_n;
         let cycle_len = n - prev_n;
-        return results
-          [(1_000_000_000 - cycle_start) % cycle_len + cycle_start];
+        return Ok(
+          results[(1_000_000_000 - cycle_start) % cycle_len + cycle_start],
+        );
       }
       hash_map::Entry::Vacant(entry) => {
         entry.insert(n);
@@ -244,15 +125,34 @@ pub fn part2(input: &str) -> usize {
       }
     };
 
-    dish.tilt_north();
-    dish.tilt_west();
-    dish.tilt_south();
-    dish.tilt_east();
+    dish.spin_cycle();
   }
 
   unreachable!();
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+  const DAY: u8 = 14;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    input.parse::<Grid<Entry>>()?;
+    Ok(())
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -262,13 +162,25 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 136);
-    assert_eq!(part1(INPUT), 112_773);
+    assert_eq!(part1(INPUT_TEST), Ok(136));
+    assert_eq!(part1(INPUT), Ok(112_773));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 64);
-    assert_eq!(part2(INPUT), 98_894);
+    assert_eq!(part2(INPUT_TEST), Ok(64));
+    assert_eq!(part2(INPUT), Ok(98_894));
+  }
+
+  #[test]
+  fn tilts_rectangular_dishes() {
+    let mut dish: Grid<Entry> = ".O.#\nO..O\n".parse().unwrap();
+
+    dish.tilt_north();
+    assert_eq!(dish.to_string(), "OO.#\n...O\n");
+    assert_eq!(dish.north_beams_load(), 5);
+
+    dish.spin_cycle();
+    assert_eq!(dish.to_string(), "..O#\n..OO\n");
   }
 }
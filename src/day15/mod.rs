@@ -1,31 +1,52 @@
 use std::array;
 
+use crate::{
+  utils::{parse_number, ParseError},
+  Solution,
+};
+
 fn hash_label(s: &str) -> u32 {
   s.chars().fold(0, |v, c| ((v + (c as u32)) * 17) & 0xFF)
 }
 
-pub fn part1(input: &str) -> u32 {
-  input.trim_end().split(',').map(hash_label).sum()
+// Steps are hashed by their ASCII codes, so anything else is rejected.
+fn steps(input: &str) -> impl Iterator<Item = Result<&str, ParseError>> {
+  input.trim_end().split(',').map(|step| {
+    match step.find(|c: char| !c.is_ascii_graphic()) {
+      Some(i) => Err(ParseError::invalid_char(input, &step[i..])),
+      None => Ok(step),
+    }
+  })
 }
 
-pub fn part2(input: &str) -> usize {
-  #[derive(Debug)]
-  enum Op {
-    Rm,
-    Eq(usize),
-  }
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+  steps(input).map(|step| Ok(hash_label(step?))).sum()
+}
+
+#[derive(Debug)]
+enum Op {
+  Rm,
+  Eq(usize),
+}
 
-  let instr = input.trim_end().split(',').map(|s| {
-    let mut c = s.char_indices();
-    let (op_pos, op) = c.find(|(_, c)| *c == '=' || *c == '-').unwrap();
-    let label = &s[..op_pos];
-    let op = match op {
-      '=' => Op::Eq(c.as_str().parse().unwrap()),
-      '-' => Op::Rm,
-      _ => panic!("invalid char"),
-    };
-    (label, op)
-  });
+fn instructions(input: &str) -> Result<Vec<(&str, Op)>, ParseError> {
+  steps(input)
+    .map(|s| {
+      let s = s?;
+      let (label, op) = if let Some((label, focal_len)) = s.split_once('=') {
+        (label, Op::Eq(parse_number(input, focal_len)?))
+      } else if let Some(label) = s.strip_suffix('-') {
+        (label, Op::Rm)
+      } else {
+        return Err(ParseError::expected(input, s, "`=` or `-`"));
+      };
+      Ok((label, op))
+    })
+    .collect()
+}
+
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+  let instr = instructions(input)?;
 
   let mut buckets: [_; 0x100] = array::from_fn(|_| Vec::new());
 
@@ -48,19 +69,42 @@ pub fn part2(input: &str) -> usize {
     }
   }
 
-  buckets
-    .into_iter()
-    .enumerate()
-    .map(|(bucket_num, lenses)| {
-      lenses
-        .into_iter()
-        .enumerate()
-        .map(|(lens_num, (_, focal_len))| {
-          (bucket_num + 1) * (lens_num + 1) * (focal_len)
-        })
-        .sum::<usize>()
-    })
-    .sum()
+  Ok(
+    buckets
+      .into_iter()
+      .enumerate()
+      .map(|(bucket_num, lenses)| {
+        lenses
+          .into_iter()
+          .enumerate()
+          .map(|(lens_num, (_, focal_len))| {
+            (bucket_num + 1) * (lens_num + 1) * (focal_len)
+          })
+          .sum::<usize>()
+      })
+      .sum(),
+  )
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+  const DAY: u8 = 15;
+
+  type Part1 = u32;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    steps(input).try_for_each(|step| step.map(drop))
+  }
+
+  fn part1(input: &str) -> Result<u32, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -72,13 +116,20 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 1320);
-    assert_eq!(part1(INPUT), 497_373);
+    assert_eq!(part1(INPUT_TEST), Ok(1320));
+    assert_eq!(part1(INPUT), Ok(497_373));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 145);
-    assert_eq!(part2(INPUT), 259_356);
+    assert_eq!(part2(INPUT_TEST), Ok(145));
+    assert_eq!(part2(INPUT), Ok(259_356));
+  }
+
+  #[test]
+  fn reports_invalid_steps() {
+    let err = part1("rn=1,c m-\n").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 7));
+    assert!(part2("rn=1,cm\n").is_err());
   }
 }
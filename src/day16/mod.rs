@@ -1,21 +1,29 @@
-use std::{
-  collections::HashSet,
-  fmt::{Display, Write},
-};
+use std::fmt::{Display, Write};
+
+use bit_set::BitSet;
 
-use crate::utils::Grid;
+use crate::{
+  utils::{
+    geom::{Direction, Point},
+    Grid, ParseError,
+  },
+  Solution,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
   EmptySpace,
   LeftMirror,
   RightMirror,
   VerticalSplitter,
   HorizontalSplitter,
+  FourWaySplitter,
+  Absorber,
+  Gate(Direction),
 }
 
 #[derive(Debug)]
-enum ParseItemError {
+pub enum ParseItemError {
   InvalidChar(char),
 }
 
@@ -29,6 +37,12 @@ impl TryFrom<char> for Tile {
       '-' => Ok(Self::HorizontalSplitter),
       '\\' => Ok(Self::LeftMirror),
       '/' => Ok(Self::RightMirror),
+      '+' => Ok(Self::FourWaySplitter),
+      '#' => Ok(Self::Absorber),
+      '^' => Ok(Self::Gate(Direction::Up)),
+      '>' => Ok(Self::Gate(Direction::Right)),
+      'v' => Ok(Self::Gate(Direction::Down)),
+      '<' => Ok(Self::Gate(Direction::Left)),
       c => Err(ParseItemError::InvalidChar(c)),
     }
   }
@@ -42,128 +56,344 @@ impl Display for Tile {
       Self::HorizontalSplitter => f.write_char('-'),
       Self::LeftMirror => f.write_char('\\'),
       Self::RightMirror => f.write_char('/'),
+      Self::FourWaySplitter => f.write_char('+'),
+      Self::Absorber => f.write_char('#'),
+      Self::Gate(dir) => f.write_char(dir.into()),
     }
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-  Up,
-  Left,
-  Down,
-  Right,
-}
+impl Tile {
+  // The directions a beam leaves this tile in when entering it moving in
+  // `dir`; this is all the tracer needs to know about a tile.
+  pub fn deflect(self, dir: Direction) -> BeamDirections {
+    match (self, dir) {
+      (Self::EmptySpace, _)
+      | (Self::HorizontalSplitter, Direction::Left | Direction::Right)
+      | (Self::VerticalSplitter, Direction::Up | Direction::Down) => {
+        BeamDirections::from_iter([dir])
+      }
+
+      (Self::LeftMirror, Direction::Left | Direction::Right)
+      | (Self::RightMirror, Direction::Up | Direction::Down) => {
+        BeamDirections::from_iter([dir.turn_right()])
+      }
+
+      (Self::LeftMirror, Direction::Up | Direction::Down)
+      | (Self::RightMirror, Direction::Left | Direction::Right) => {
+        BeamDirections::from_iter([dir.turn_left()])
+      }
 
-impl Direction {
-  fn step(self, [x, y]: [isize; 2]) -> [isize; 2] {
-    match self {
-      Direction::Up => [x, y - 1],
-      Direction::Left => [x - 1, y],
-      Direction::Down => [x, y + 1],
-      Direction::Right => [x + 1, y],
+      (Self::HorizontalSplitter, Direction::Up | Direction::Down)
+      | (Self::VerticalSplitter, Direction::Left | Direction::Right) => {
+        BeamDirections::from_iter([dir.turn_right(), dir.turn_left()])
+      }
+
+      (Self::FourWaySplitter, _) => {
+        BeamDirections::from_iter(Direction::variants())
+      }
+
+      (Self::Gate(gate_dir), dir) if gate_dir == dir => {
+        BeamDirections::from_iter([dir])
+      }
+
+      (Self::Absorber | Self::Gate(_), _) => BeamDirections::default(),
     }
   }
+}
+
+// Visited states are kept in dense bitsets indexed by cell and direction,
+// and reused between traces, so that tracing every edge start stays cheap.
+struct BeamTracer<'a> {
+  grid: &'a Grid<Tile>,
+  visited: BitSet,
+  energized: BitSet,
+  queue: Vec<(Point<isize>, Direction)>,
+}
+
+impl<'a> BeamTracer<'a> {
+  fn new(grid: &'a Grid<Tile>) -> Self {
+    let cells = (grid.rows() * grid.cols()) as usize;
 
-  fn turn_left(self) -> Direction {
-    match self {
-      Direction::Up => Direction::Left,
-      Direction::Left => Direction::Down,
-      Direction::Down => Direction::Right,
-      Direction::Right => Direction::Up,
+    Self {
+      grid,
+      visited: BitSet::with_capacity(cells * 4),
+      energized: BitSet::with_capacity(cells),
+      queue: Vec::new(),
     }
   }
 
-  fn turn_right(self) -> Direction {
-    match self {
-      Direction::Up => Direction::Right,
-      Direction::Left => Direction::Up,
-      Direction::Down => Direction::Left,
-      Direction::Right => Direction::Down,
+  fn trace(&mut self, start_pos: Point<isize>, start_dir: Direction) {
+    self.visited.clear();
+    self.energized.clear();
+    self.queue.push((start_pos, start_dir));
+
+    while let Some((pos, dir)) = self.queue.pop() {
+      let Some(&tile) = self.grid.get(pos.into()) else {
+        continue;
+      };
+
+      let cell = (pos.y * self.grid.cols() + pos.x) as usize;
+
+      if !self.visited.insert(cell * 4 + dir as usize) {
+        continue;
+      }
+
+      self.energized.insert(cell);
+
+      for dir in tile.deflect(dir).iter() {
+        self.queue.push((dir.step(pos), dir));
+      }
     }
   }
-}
 
-impl Grid<Tile> {
+  fn beam_directions(&self) -> Grid<BeamDirections> {
+    let rows = self.grid.rows() as usize;
+    let cols = self.grid.cols() as usize;
+
+    Grid::from_fn(rows, cols, |[x, y]| {
+      let cell = y as usize * cols + x as usize;
+      let mask = (0..4)
+        .filter(|dir| self.visited.contains(cell * 4 + dir))
+        .fold(0, |mask, dir| mask | 1 << dir);
+      BeamDirections(mask)
+    })
+  }
+
   fn energized_len(
-    &self,
-    start_pos: [isize; 2],
+    &mut self,
+    start_pos: Point<isize>,
     start_dir: Direction,
   ) -> usize {
-    let mut queue = Vec::new();
-    let mut visited = HashSet::new();
-
-    queue.push((start_pos, start_dir));
-
-    while let Some((pos, dir)) = queue.pop() {
-      if !visited.contains(&(pos, dir)) {
-        if let Some(tile) = self.get(pos) {
-          visited.insert((pos, dir));
-          match (tile, dir) {
-            (Tile::EmptySpace, _)
-            | (Tile::HorizontalSplitter, Direction::Left | Direction::Right)
-            | (Tile::VerticalSplitter, Direction::Up | Direction::Down) => {
-              queue.push((dir.step(pos), dir));
-            }
-
-            (Tile::LeftMirror, Direction::Left | Direction::Right)
-            | (Tile::RightMirror, Direction::Up | Direction::Down) => {
-              let dir = dir.turn_right();
-              queue.push((dir.step(pos), dir));
-            }
-
-            (Tile::LeftMirror, Direction::Up | Direction::Down)
-            | (Tile::RightMirror, Direction::Left | Direction::Right) => {
-              let dir = dir.turn_left();
-              queue.push((dir.step(pos), dir));
-            }
-
-            (Tile::HorizontalSplitter, Direction::Up | Direction::Down)
-            | (Tile::VerticalSplitter, Direction::Left | Direction::Right) => {
-              {
-                let dir = dir.turn_right();
-                queue.push((dir.step(pos), dir));
-              }
-
-              {
-                let dir = dir.turn_left();
-                queue.push((dir.step(pos), dir));
-              }
-            }
+    self.trace(start_pos, start_dir);
+    self.energized.len()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BeamDirections(u8);
+
+impl FromIterator<Direction> for BeamDirections {
+  fn from_iter<I: IntoIterator<Item = Direction>>(iter: I) -> Self {
+    Self(iter.into_iter().fold(0, |mask, dir| mask | 1 << dir as u8))
+  }
+}
+
+impl BeamDirections {
+  pub fn contains(self, dir: Direction) -> bool {
+    self.0 & 1 << dir as u8 != 0
+  }
+
+  pub fn iter(self) -> impl Iterator<Item = Direction> {
+    Direction::variants()
+      .into_iter()
+      .filter(move |&dir| self.contains(dir))
+  }
+
+  pub fn len(self) -> usize {
+    self.0.count_ones() as usize
+  }
+
+  pub fn is_empty(self) -> bool {
+    self.0 == 0
+  }
+}
+
+pub struct BeamMap<'a> {
+  grid: &'a Grid<Tile>,
+  beams: Grid<BeamDirections>,
+}
+
+impl BeamMap<'_> {
+  pub fn directions(&self, pos: Point<isize>) -> BeamDirections {
+    self.beams.get(pos.into()).copied().unwrap_or_default()
+  }
+
+  pub fn energized(&self) -> impl Iterator<Item = Point<isize>> + '_ {
+    self
+      .beams
+      .iter()
+      .filter(|(_, beams)| !beams.is_empty())
+      .map(|(pos, _)| pos.into())
+  }
+
+  pub fn energized_len(&self) -> usize {
+    self.energized().count()
+  }
+
+  pub fn energized_map(&self) -> EnergizedMap<'_> {
+    EnergizedMap(self)
+  }
+
+  pub fn overlay(&self) -> BeamOverlay<'_> {
+    BeamOverlay(self)
+  }
+}
+
+// The classic `#`/`.` map of energized tiles.
+pub struct EnergizedMap<'a>(&'a BeamMap<'a>);
+
+impl Display for EnergizedMap<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for y in 0..self.0.beams.rows() {
+      for x in self.0.beams.row(y) {
+        f.write_char(if x.is_empty() { '.' } else { '#' })?;
+      }
+      writeln!(f)?;
+    }
+
+    Ok(())
+  }
+}
+
+// The contraption with beams drawn over empty space: an arrow for a single
+// beam, or the number of beams passing through.
+pub struct BeamOverlay<'a>(&'a BeamMap<'a>);
+
+impl Display for BeamOverlay<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let BeamMap { grid, beams } = self.0;
+
+    for y in 0..grid.rows() {
+      for (tile, &beams) in grid.row(y).zip(beams.row(y)) {
+        match (tile, beams.len()) {
+          (Tile::EmptySpace, 1) => {
+            f.write_char(beams.iter().next().unwrap().into())?
           }
+          (Tile::EmptySpace, len @ 2..) => write!(f, "{len}")?,
+          (tile, _) => write!(f, "{tile}")?,
         }
       }
+      writeln!(f)?;
     }
 
-    visited
+    Ok(())
+  }
+}
+
+impl Grid<Tile> {
+  pub fn beams(
+    &self,
+    start_pos: Point<isize>,
+    start_dir: Direction,
+  ) -> BeamMap<'_> {
+    let mut tracer = BeamTracer::new(self);
+    tracer.trace(start_pos, start_dir);
+
+    BeamMap {
+      grid: self,
+      beams: tracer.beam_directions(),
+    }
+  }
+
+  fn energized_len(
+    &self,
+    start_pos: Point<isize>,
+    start_dir: Direction,
+  ) -> usize {
+    BeamTracer::new(self).energized_len(start_pos, start_dir)
+  }
+
+  pub fn edge_entries(
+    &self,
+  ) -> impl Iterator<Item = (Point<isize>, Direction)> {
+    let rows = self.rows();
+    let cols = self.cols();
+
+    (0..rows)
+      .flat_map(move |y| {
+        [
+          (Point::new(0, y), Direction::Right),
+          (Point::new(cols - 1, y), Direction::Left),
+        ]
+      })
+      .chain((0..cols).flat_map(move |x| {
+        [
+          (Point::new(x, 0), Direction::Down),
+          (Point::new(x, rows - 1), Direction::Up),
+        ]
+      }))
+  }
+
+  pub fn rank_entries(
+    &self,
+    candidates: impl IntoIterator<Item = (Point<isize>, Direction)>,
+  ) -> EntryRanking {
+    let mut tracer = BeamTracer::new(self);
+
+    let mut entries = candidates
       .into_iter()
-      .map(|(pos, _)| pos)
-      .collect::<HashSet<_>>()
-      .len()
+      .map(|(pos, dir)| RankedEntry {
+        pos,
+        dir,
+        energized: tracer.energized_len(pos, dir),
+      })
+      .collect::<Vec<_>>();
+
+    // Stable, so ties keep the order the candidates were given in.
+    entries.sort_by(|a, b| b.energized.cmp(&a.energized));
+
+    EntryRanking { entries }
   }
 }
 
-pub fn part1(input: &str) -> usize {
-  let grid: Grid<Tile> = input.parse().unwrap();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RankedEntry {
+  pub pos: Point<isize>,
+  pub dir: Direction,
+  pub energized: usize,
+}
 
-  grid.energized_len([0isize, 0], Direction::Right)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryRanking {
+  pub entries: Vec<RankedEntry>,
 }
 
-pub fn part2(input: &str) -> usize {
-  let grid: Grid<Tile> = input.parse().unwrap();
+impl EntryRanking {
+  pub fn best(&self) -> Option<&RankedEntry> {
+    self.entries.first()
+  }
+}
 
-  let rows = grid.rows();
-  let cols = grid.cols();
+pub fn parse_grid(input: &str) -> Result<Grid<Tile>, ParseError> {
+  Ok(input.parse()?)
+}
 
-  (0..rows)
-    .flat_map(|y| {
-      [([0, y], Direction::Right), ([cols - 1, y], Direction::Left)]
-    })
-    .chain((0..cols).flat_map(|x| {
-      [([x, 0], Direction::Down), ([x, rows - 1], Direction::Up)]
-    }))
-    .map(|(pos, dir)| grid.energized_len(pos, dir))
-    .max()
-    .unwrap()
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+  let grid = parse_grid(input)?;
+
+  Ok(grid.energized_len(Point::new(0, 0), Direction::Right))
+}
+
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+  let grid = parse_grid(input)?;
+
+  let ranking = grid.rank_entries(grid.edge_entries());
+
+  ranking.best().map(|entry| entry.energized).ok_or_else(|| {
+    ParseError::expected(input, &input[input.len()..], "at least one tile")
+  })
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+  const DAY: u8 = 16;
+
+  type Part1 = usize;
+  type Part2 = usize;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_grid(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<usize, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<usize, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -175,14 +405,114 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 46);
-    assert_eq!(part1(INPUT), 7798);
+    assert_eq!(part1(INPUT_TEST), Ok(46));
+    assert_eq!(part1(INPUT), Ok(7798));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 51);
-    // Too slow, take ≈5s
-    // assert_eq!(part2(INPUT), 8026);
+    assert_eq!(part2(INPUT_TEST), Ok(51));
+    assert_eq!(part2(INPUT), Ok(8026));
+    assert_eq!(part2("").unwrap_err().snippet, "");
+  }
+
+  #[test]
+  fn ranks_entries() {
+    let grid: Grid<Tile> = INPUT_TEST.parse().unwrap();
+
+    let ranking = grid.rank_entries(grid.edge_entries());
+    assert_eq!(ranking.entries.len(), 40);
+    assert_eq!(
+      ranking.best(),
+      Some(&RankedEntry {
+        pos: Point::new(3, 0),
+        dir: Direction::Down,
+        energized: 51,
+      })
+    );
+    assert!(ranking
+      .entries
+      .windows(2)
+      .all(|pair| pair[0].energized >= pair[1].energized));
+
+    let interior = grid.rank_entries([
+      (Point::new(0, 0), Direction::Right),
+      (Point::new(4, 4), Direction::Left),
+      (Point::new(20, 20), Direction::Up),
+    ]);
+    let counts = interior
+      .entries
+      .iter()
+      .map(|entry| (entry.pos, entry.energized))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      counts,
+      [
+        (Point::new(0, 0), 46),
+        (Point::new(4, 4), 5),
+        (Point::new(20, 20), 0),
+      ]
+    );
+  }
+
+  #[test]
+  fn traces_extended_tiles() {
+    let grid: Grid<Tile> = ".>.#\n.+..\n.<..\n".parse().unwrap();
+    assert_eq!(grid.to_string(), ".>.#\n.+..\n.<..\n");
+
+    let beams = grid.beams(Point::new(0, 0), Direction::Right);
+    assert_eq!(
+      beams.energized_map().to_string(),
+      "####\n\
+       ....\n\
+       ....\n"
+    );
+
+    let beams = grid.beams(Point::new(0, 1), Direction::Right);
+    assert_eq!(
+      beams.energized_map().to_string(),
+      ".#..\n\
+       ####\n\
+       .#..\n"
+    );
+  }
+
+  #[test]
+  fn renders_beams() {
+    let grid: Grid<Tile> = INPUT_TEST.parse().unwrap();
+    let beams = grid.beams(Point::new(0, 0), Direction::Right);
+
+    assert_eq!(beams.energized_len(), 46);
+    assert!(beams.directions(Point::new(0, 7)).contains(Direction::Left));
+    assert_eq!(beams.directions(Point::new(5, 6)).len(), 2);
+    assert!(beams.directions(Point::new(9, 9)).is_empty());
+
+    assert_eq!(
+      beams.energized_map().to_string(),
+      "######....\n\
+       .#...#....\n\
+       .#...#####\n\
+       .#...##...\n\
+       .#...##...\n\
+       .#...##...\n\
+       .#..####..\n\
+       ########..\n\
+       .#######..\n\
+       .#...#.#..\n"
+    );
+
+    assert_eq!(
+      beams.overlay().to_string(),
+      ">|<<<\\....\n\
+       |v-.\\^....\n\
+       .v...|->>>\n\
+       .v...v^.|.\n\
+       .v...v^...\n\
+       .v...v^..\\\n\
+       .v../2\\\\..\n\
+       <->-/vv|..\n\
+       .|<<<2-|.\\\n\
+       .v//.|.v..\n"
+    );
   }
 }
@@ -1,14 +1,19 @@
 use std::{
-  collections::{BinaryHeap, HashSet},
-  convert::Infallible,
-  fmt::Display,
+  collections::{HashMap, HashSet},
+  fmt::{Display, Write},
   ops::Deref,
 };
 
-use crate::utils::Grid;
+use crate::{
+  utils::{
+    geom::{Point, Vector},
+    search, Grid, ParseError,
+  },
+  Solution,
+};
 
 #[derive(Debug)]
-struct Digit(u8);
+pub struct Digit(pub u8);
 
 impl Deref for Digit {
   type Target = u8;
@@ -18,11 +23,19 @@ impl Deref for Digit {
   }
 }
 
+#[derive(Debug)]
+pub enum ParseItemError {
+  InvalidChar(char),
+}
+
 impl TryFrom<char> for Digit {
-  type Error = Infallible;
+  type Error = ParseItemError;
 
   fn try_from(c: char) -> Result<Self, Self::Error> {
-    Ok(Self(c.to_digit(10).unwrap().try_into().unwrap()))
+    match c.to_digit(10) {
+      Some(digit) => Ok(Self(digit as u8)),
+      None => Err(ParseItemError::InvalidChar(c)),
+    }
   }
 }
 
@@ -32,74 +45,98 @@ impl Display for Digit {
   }
 }
 
+type Position = Point<isize>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-  Up,
-  Left,
-  Down,
-  Right,
+struct Crucible {
+  pos: Position,
+  heading: Vector<isize>,
+  steps: usize,
 }
 
-impl Direction {
-  const fn variants() -> [Self; 4] {
-    [Self::Up, Self::Left, Self::Down, Self::Right]
-  }
+// Orthogonal headings first, so that `&HEADINGS[..4]` excludes diagonals.
+const HEADINGS: [Vector<isize>; 8] = [
+  Vector::new(0, -1),
+  Vector::new(1, 0),
+  Vector::new(0, 1),
+  Vector::new(-1, 0),
+  Vector::new(1, -1),
+  Vector::new(1, 1),
+  Vector::new(-1, 1),
+  Vector::new(-1, -1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrucibleRules {
+  pub starts: Vec<Position>,
+  pub goals: HashSet<Position>,
+  pub min_steps: usize,
+  pub max_steps: usize,
+  pub may_reverse: bool,
+  pub diagonal: bool,
+  pub turn_penalty: u32,
+}
 
-  fn step(self, [x, y]: [isize; 2]) -> [isize; 2] {
-    match self {
-      Direction::Up => [x, y - 1],
-      Direction::Left => [x - 1, y],
-      Direction::Down => [x, y + 1],
-      Direction::Right => [x + 1, y],
+impl CrucibleRules {
+  pub fn new(start: Position, goal: Position) -> Self {
+    Self {
+      starts: vec![start],
+      goals: HashSet::from([goal]),
+      min_steps: 0,
+      max_steps: usize::MAX,
+      may_reverse: false,
+      diagonal: false,
+      turn_penalty: 0,
     }
   }
 
-  fn flip(self) -> Self {
-    match self {
-      Direction::Up => Direction::Down,
-      Direction::Left => Direction::Right,
-      Direction::Down => Direction::Up,
-      Direction::Right => Direction::Left,
+  pub fn straight_steps(self, min_steps: usize, max_steps: usize) -> Self {
+    Self {
+      min_steps,
+      max_steps,
+      ..self
     }
   }
 }
 
-type Position = [isize; 2];
-
-#[derive(Eq, PartialEq)]
-struct QueueEntry {
-  pos: Position,
-  dir: Direction,
-  cost: u32,
-  steps: usize,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeatLossPath {
+  pub heat_loss: u32,
+  pub steps: Vec<(Position, Vector<isize>)>,
 }
 
-#[derive(Eq, PartialEq, Hash)]
-struct VisitedKey {
-  pos: Position,
-  dir: Direction,
-  steps: usize,
-}
-
-impl From<QueueEntry> for VisitedKey {
-  fn from(
-    QueueEntry {
-      pos, dir, steps, ..
-    }: QueueEntry,
-  ) -> Self {
-    Self { pos, dir, steps }
+fn arrow(heading: Vector<isize>) -> char {
+  match (heading.x.signum(), heading.y.signum()) {
+    (0, -1) => '^',
+    (1, 0) => '>',
+    (0, 1) => 'v',
+    (-1, 0) => '<',
+    (1, -1) | (-1, 1) => '/',
+    _ => '\\',
   }
 }
 
-impl std::cmp::Ord for QueueEntry {
-  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    self.cost.cmp(&other.cost).reverse()
-  }
+pub struct PathOverlay<'a> {
+  grid: &'a Grid<Digit>,
+  path: &'a HeatLossPath,
 }
 
-impl std::cmp::PartialOrd for QueueEntry {
-  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-    Some(self.cmp(other))
+impl Display for PathOverlay<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let arrows = self.path.steps.iter().copied().collect::<HashMap<_, _>>();
+
+    for y in 0..self.grid.rows() {
+      for x in 0..self.grid.cols() {
+        let pos = Point::new(x, y);
+        match arrows.get(&pos) {
+          Some(&heading) => f.write_char(arrow(heading))?,
+          None => write!(f, "{}", self.grid[pos])?,
+        }
+      }
+      writeln!(f)?;
+    }
+
+    Ok(())
   }
 }
 
@@ -109,104 +146,278 @@ impl Grid<Digit> {
     min_steps: usize,
     max_steps: usize,
   ) -> Option<u32> {
-    let rows = self.rows();
-    let cols = self.cols();
+    let path = self.least_heat_loss_path(min_steps, max_steps)?;
+    Some(path.heat_loss)
+  }
 
-    let start_pos: Position = [0, 0];
-    let end_pos: Position = [cols - 1, rows - 1];
+  pub fn render_path<'a>(&'a self, path: &'a HeatLossPath) -> PathOverlay<'a> {
+    PathOverlay { grid: self, path }
+  }
 
-    let mut visited = HashSet::<VisitedKey>::new();
-    let mut queue = BinaryHeap::<QueueEntry>::new();
+  pub fn corner_rules(&self) -> CrucibleRules {
+    let start = Point::new(0, 0);
+    let goal = Point::new(self.cols() - 1, self.rows() - 1);
 
-    queue.push(QueueEntry {
-      pos: start_pos,
-      dir: Direction::Down,
-      cost: 0,
-      steps: 0,
-    });
+    CrucibleRules::new(start, goal)
+  }
 
-    queue.push(QueueEntry {
-      pos: start_pos,
-      dir: Direction::Right,
-      cost: 0,
-      steps: 0,
-    });
+  pub fn least_heat_loss_path(
+    &self,
+    min_steps: usize,
+    max_steps: usize,
+  ) -> Option<HeatLossPath> {
+    let rules = self.corner_rules().straight_steps(min_steps, max_steps);
+    self.least_heat_loss_with(&rules)
+  }
 
-    while let Some(QueueEntry {
-      pos,
-      dir,
-      cost,
-      steps,
-    }) = queue.pop()
-    {
-      if pos == end_pos && steps >= min_steps {
-        return Some(cost);
-      }
+  pub fn least_heat_loss_with(
+    &self,
+    rules: &CrucibleRules,
+  ) -> Option<HeatLossPath> {
+    let headings = if rules.diagonal {
+      &HEADINGS[..]
+    } else {
+      &HEADINGS[..4]
+    };
+
+    // A crucible that hasn't moved yet may leave in any direction.
+    let inside = |&&pos: &&Position| self.get(pos.into()).is_some();
+    let starts = rules.starts.iter().filter(inside).flat_map(|&pos| {
+      headings.iter().map(move |&heading| Crucible {
+        pos,
+        heading,
+        steps: 0,
+      })
+    });
 
-      for next_dir in Direction::variants() {
-        if next_dir == dir.flip()
-          || (next_dir == dir && steps >= max_steps)
-          || (next_dir != dir && steps < min_steps)
+    // Straight runs can't outlast the grid, and once they can't reach
+    // `max_steps` any run past `min_steps` behaves the same; capping the
+    // stored steps at that point keeps the state space small.
+    let extent = self.rows().max(self.cols()) as usize;
+    let step_limit = if rules.max_steps >= extent {
+      rules.min_steps.min(extent)
+    } else {
+      rules.max_steps
+    };
+
+    let len = self.rows() as usize
+      * self.cols() as usize
+      * headings.len()
+      * (step_limit + 1);
+    let index = |crucible: &Crucible| {
+      let heading = headings
+        .iter()
+        .position(|&heading| heading == crucible.heading)
+        .unwrap();
+      let cell = (crucible.pos.y * self.cols() + crucible.pos.x) as usize;
+      (cell * headings.len() + heading) * (step_limit + 1) + crucible.steps
+    };
+
+    let successors = |&Crucible {
+                        pos,
+                        heading,
+                        steps,
+                      }: &Crucible| {
+      headings.iter().filter_map(move |&next_heading| {
+        let straight = next_heading == heading;
+
+        if (next_heading == -heading && !rules.may_reverse)
+          || (straight && steps >= rules.max_steps)
+          || (!straight && steps < rules.min_steps)
         {
-          continue;
+          return None;
         }
 
-        let next_pos = next_dir.step(pos);
+        let next_pos = pos + next_heading;
+        let &Digit(weight) = self.get(next_pos.into())?;
+        let penalty = if straight { 0 } else { rules.turn_penalty };
 
-        let Some(&Digit(weight)) = self.get(next_pos) else {
-          continue;
+        let next = Crucible {
+          pos: next_pos,
+          heading: next_heading,
+          steps: if straight { steps + 1 } else { 1 }.min(step_limit),
         };
 
-        let next_cost = cost + (weight as u32);
-        let next_steps = if next_dir == dir { steps + 1 } else { 1 };
+        Some((next, weight as u32 + penalty))
+      })
+    };
+
+    let is_goal = |crucible: &Crucible| {
+      rules.goals.contains(&crucible.pos) && crucible.steps >= rules.min_steps
+    };
+
+    let result =
+      search::dijkstra_dense(len, index, starts, successors, is_goal)?;
+
+    Some(HeatLossPath {
+      heat_loss: result.cost,
+      steps: result
+        .path
+        .into_iter()
+        .skip(1)
+        .map(|crucible| (crucible.pos, crucible.heading))
+        .collect(),
+    })
+  }
+}
 
-        if visited.insert(VisitedKey {
-          pos: next_pos,
-          dir: next_dir,
-          steps: next_steps,
-        }) {
-          queue.push(QueueEntry {
-            pos: next_pos,
-            dir: next_dir,
-            cost: next_cost,
-            steps: next_steps,
-          });
-        }
-      }
-    }
+pub fn parse_grid(input: &str) -> Result<Grid<Digit>, ParseError> {
+  Ok(input.parse()?)
+}
 
-    None
-  }
+fn least_heat_loss(
+  input: &str,
+  min_steps: usize,
+  max_steps: usize,
+) -> Result<u32, ParseError> {
+  parse_grid(input)?
+    .least_heat_loss(min_steps, max_steps)
+    .ok_or_else(|| {
+      let end = &input[input.len()..];
+      ParseError::expected(input, end, "a path to the bottom right corner")
+    })
 }
 
-pub fn part1(input: &str) -> u32 {
-  let grid: Grid<Digit> = input.parse().unwrap();
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+  least_heat_loss(input, 0, 3)
+}
 
-  grid.least_heat_loss(0, 3).unwrap()
+pub fn part2(input: &str) -> Result<u32, ParseError> {
+  least_heat_loss(input, 4, 10)
 }
 
-pub fn part2(input: &str) -> u32 {
-  let grid: Grid<Digit> = input.parse().unwrap();
+pub struct Day17;
+
+impl Solution for Day17 {
+  const DAY: u8 = 17;
 
-  grid.least_heat_loss(4, 10).unwrap()
+  type Part1 = u32;
+  type Part2 = u32;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_grid(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<u32, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<u32, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::utils::ParseErrorKind;
 
   const INPUT: &str = include_str!("input.txt");
   const INPUT_TEST: &str = include_str!("input_test.txt");
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 102);
-    assert_eq!(part1(INPUT), 1008);
+    assert_eq!(part1(INPUT_TEST), Ok(102));
+    assert_eq!(part1(INPUT), Ok(1008));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 94);
-    assert_eq!(part2(INPUT), 1210);
+    assert_eq!(part2(INPUT_TEST), Ok(94));
+    assert_eq!(part2(INPUT), Ok(1210));
+  }
+
+  #[test]
+  fn renders_least_heat_loss_path() {
+    let grid: Grid<Digit> = INPUT_TEST.parse().unwrap();
+    let path = grid.least_heat_loss_path(0, 3).unwrap();
+
+    assert_eq!(path.heat_loss, 102);
+    assert_eq!(
+      path.heat_loss,
+      path.steps.iter().map(|&(pos, _)| *grid[pos] as u32).sum()
+    );
+    assert_eq!(path.steps.last().unwrap().0, Point::new(12, 12));
+
+    assert_eq!(
+      grid.render_path(&path).to_string(),
+      "2>>34^>>>1323\n\
+       32v>>>35v5623\n\
+       32552456v>>54\n\
+       3446585845v52\n\
+       4546657867v>6\n\
+       14385987984v4\n\
+       44578769877v6\n\
+       36378779796v>\n\
+       465496798688v\n\
+       456467998645v\n\
+       12246868655<v\n\
+       25465488877v5\n\
+       43226746555v>\n"
+    );
+  }
+
+  #[test]
+  fn supports_custom_crucible_rules() {
+    let grid: Grid<Digit> = INPUT_TEST.parse().unwrap();
+    let part1_rules = grid.corner_rules().straight_steps(0, 3);
+    let heat_loss = |rules: &CrucibleRules| {
+      grid.least_heat_loss_with(rules).map(|path| path.heat_loss)
+    };
+
+    assert_eq!(heat_loss(&part1_rules), Some(102));
+
+    let short_hop = CrucibleRules::new(Point::new(0, 0), Point::new(2, 0));
+    assert_eq!(heat_loss(&short_hop), Some(4 + 1));
+
+    let multiple_starts = CrucibleRules {
+      starts: vec![Point::new(0, 0), Point::new(3, 0)],
+      ..short_hop.clone()
+    };
+    assert_eq!(heat_loss(&multiple_starts), Some(1));
+
+    let outside = CrucibleRules::new(Point::new(-1, 0), Point::new(2, 0));
+    assert_eq!(heat_loss(&outside), None);
+
+    let diagonal = CrucibleRules {
+      diagonal: true,
+      ..part1_rules.clone()
+    };
+    assert!(heat_loss(&diagonal).unwrap() < 102);
+
+    let reversing = CrucibleRules {
+      may_reverse: true,
+      ..part1_rules.clone()
+    };
+    assert!(heat_loss(&reversing).unwrap() <= 102);
+
+    let penalized = CrucibleRules {
+      turn_penalty: 5,
+      ..part1_rules.clone()
+    };
+    let path = grid.least_heat_loss_with(&penalized).unwrap();
+    let turns = path
+      .steps
+      .windows(2)
+      .filter(|pair| pair[0].1 != pair[1].1)
+      .count() as u32;
+    let weights: u32 =
+      path.steps.iter().map(|&(pos, _)| *grid[pos] as u32).sum();
+    assert_eq!(path.heat_loss, weights + 5 * turns);
+    assert!(path.heat_loss > 102);
+  }
+
+  #[test]
+  fn reports_invalid_digits() {
+    let err = part1("123\n4x6\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 2));
+    assert_eq!(err.snippet, "x");
+
+    let err = part2("11\n").unwrap_err();
+    assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 1, ""));
+    assert_eq!(
+      err.kind,
+      ParseErrorKind::Expected("a path to the bottom right corner")
+    );
   }
 }
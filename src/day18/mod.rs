@@ -1,51 +1,60 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-  Up,
-  Down,
-  Left,
-  Right,
-}
+use crate::{
+  utils::{
+    geom::{Direction, Point},
+    parse_number, ParseError,
+  },
+  Solution,
+};
+
+fn parse_input(input: &str) -> Result<Vec<(Direction, u8, &str)>, ParseError> {
+  input
+    .lines()
+    .map(|line| {
+      let mut words = line.split_whitespace();
+
+      let (Some(dir), Some(len), Some(color), None) =
+        (words.next(), words.next(), words.next(), words.next())
+      else {
+        return Err(ParseError::expected(
+          input,
+          line,
+          "a direction, a length and a color",
+        ));
+      };
+
+      let dir = match dir {
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        "L" => Direction::Left,
+        "R" => Direction::Right,
+        _ => {
+          return Err(ParseError::expected(input, dir, "`U`, `D`, `L` or `R`"))
+        }
+      };
+
+      let len = parse_number(input, len)?;
 
-fn parse_input(input: &str) -> impl Iterator<Item = (Direction, u8, u32)> + '_ {
-  input.lines().map(|line| {
-    let mut words = line.split_whitespace();
-    let dir = match words.next().unwrap() {
-      "U" => Direction::Up,
-      "D" => Direction::Down,
-      "L" => Direction::Left,
-      "R" => Direction::Right,
-      _ => panic!(),
-    };
-
-    let len = words.next().unwrap().parse().unwrap();
-
-    let color = u32::from_str_radix(
-      words
-        .next()
-        .unwrap()
-        .trim_start_matches("(#")
-        .trim_end_matches(')'),
-      16,
-    )
-    .unwrap();
-
-    (dir, len, color)
-  })
+      let color = color
+        .strip_prefix("(#")
+        .and_then(|color| color.strip_suffix(')'))
+        .filter(|color| {
+          color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit())
+        })
+        .ok_or_else(|| ParseError::expected(input, color, "`(#rrggbb)`"))?;
+
+      Ok((dir, len, color))
+    })
+    .collect()
 }
 
 fn solve(iter: impl Iterator<Item = (Direction, i64)>) -> i64 {
   let mut twice_area = 0;
   let mut circumference = 0;
-  let mut pos = [0, 0];
+  let mut pos = Point::new(0, 0);
 
   for (dir, len) in iter {
-    let next_pos = match dir {
-      Direction::Up => [pos[0], pos[1] - len],
-      Direction::Down => [pos[0], pos[1] + len],
-      Direction::Left => [pos[0] - len, pos[1]],
-      Direction::Right => [pos[0] + len, pos[1]],
-    };
-    twice_area += (pos[0] + next_pos[0]) * (pos[1] - next_pos[1]);
+    let next_pos = pos + dir.vector() * len;
+    twice_area += (pos.x + next_pos.x) * (pos.y - next_pos.y);
     circumference += len;
     pos = next_pos;
   }
@@ -53,24 +62,58 @@ fn solve(iter: impl Iterator<Item = (Direction, i64)>) -> i64 {
   twice_area.abs() / 2 + (circumference / 2 + 1)
 }
 
-pub fn part1(input: &str) -> i64 {
-  solve(parse_input(input).map(|(dir, len, _)| (dir, len as i64)))
+pub fn part1(input: &str) -> Result<i64, ParseError> {
+  let plan = parse_input(input)?;
+
+  Ok(solve(
+    plan.into_iter().map(|(dir, len, _)| (dir, len as i64)),
+  ))
 }
 
-pub fn part2(input: &str) -> i64 {
-  solve(parse_input(input).map(|(_, _, color)| {
-    let dir = match color & 0b111 {
-      0 => Direction::Right,
-      1 => Direction::Down,
-      2 => Direction::Left,
-      3 => Direction::Up,
-      _ => panic!(),
-    };
+pub fn part2(input: &str) -> Result<i64, ParseError> {
+  let plan = parse_input(input)?
+    .into_iter()
+    .map(|(_, _, color)| {
+      let (len, dir) = color.split_at(5);
+
+      let dir = match dir {
+        "0" => Direction::Right,
+        "1" => Direction::Down,
+        "2" => Direction::Left,
+        "3" => Direction::Up,
+        _ => {
+          return Err(ParseError::expected(input, dir, "`0`, `1`, `2` or `3`"))
+        }
+      };
 
-    let len = color >> 4;
+      let len = i64::from_str_radix(len, 16).unwrap();
 
-    (dir, len as i64)
-  }))
+      Ok((dir, len))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(solve(plan.into_iter()))
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+  const DAY: u8 = 18;
+
+  type Part1 = i64;
+  type Part2 = i64;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_input(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<i64, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<i64, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
@@ -82,13 +125,13 @@ mod tests {
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 62);
-    assert_eq!(part1(INPUT), 40_131);
+    assert_eq!(part1(INPUT_TEST), Ok(62));
+    assert_eq!(part1(INPUT), Ok(40_131));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 952_408_144_115);
-    assert_eq!(part2(INPUT), 104_454_050_898_331);
+    assert_eq!(part2(INPUT_TEST), Ok(952_408_144_115));
+    assert_eq!(part2(INPUT), Ok(104_454_050_898_331));
   }
 }
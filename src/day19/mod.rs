@@ -1,253 +1,1455 @@
 use std::{
   collections::{HashMap, HashSet},
+  fmt::Display,
   ops::Range,
 };
 
-#[derive(Debug)]
-enum RuleOperator {
+use crate::{
+  utils::{parse_number, ParseError},
+  Solution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleOperator {
   LessThan,
   GreaterThan,
+  LessOrEqual,
+  GreaterOrEqual,
+  Equal,
+  NotEqual,
+}
+
+impl RuleOperator {
+  // Two-character operators come first, so that they are matched before
+  // their one-character prefixes.
+  const SYMBOLS: [(&'static str, Self); 6] = [
+    ("<=", Self::LessOrEqual),
+    (">=", Self::GreaterOrEqual),
+    ("==", Self::Equal),
+    ("!=", Self::NotEqual),
+    ("<", Self::LessThan),
+    (">", Self::GreaterThan),
+  ];
+
+  pub fn test(self, lhs: u64, rhs: u64) -> bool {
+    match self {
+      RuleOperator::LessThan => lhs < rhs,
+      RuleOperator::GreaterThan => lhs > rhs,
+      RuleOperator::LessOrEqual => lhs <= rhs,
+      RuleOperator::GreaterOrEqual => lhs >= rhs,
+      RuleOperator::Equal => lhs == rhs,
+      RuleOperator::NotEqual => lhs != rhs,
+    }
+  }
+}
+
+impl Display for RuleOperator {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let (symbol, _) = Self::SYMBOLS
+      .iter()
+      .find(|&&(_, operator)| operator == *self)
+      .unwrap();
+    write!(f, "{symbol}")
+  }
 }
 
-#[derive(Debug)]
-struct RuleTest<'a> {
-  category: &'a str,
-  operator: RuleOperator,
-  value: u64,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleTest<'a> {
+  pub category: &'a str,
+  pub operator: RuleOperator,
+  pub value: u64,
 }
 
-type Part<'a> = HashMap<&'a str, u64>;
-type Workflow<'a> = Vec<Rule<'a>>;
-type Workflows<'a> = HashMap<&'a str, Workflow<'a>>;
+const UNBOUNDED: Range<u64> = 0..u64::MAX;
+
+pub type Part<'a> = HashMap<&'a str, u64>;
+pub type Workflow<'a> = Vec<Rule<'a>>;
+pub type Workflows<'a> = HashMap<&'a str, Workflow<'a>>;
 
 impl RuleTest<'_> {
-  fn call(&self, part: &Part) -> bool {
-    let part_value = part[self.category];
-    match self.operator {
-      RuleOperator::LessThan => part_value < self.value,
-      RuleOperator::GreaterThan => part_value > self.value,
+  pub fn call(&self, part: &Part) -> bool {
+    self.operator.test(part[self.category], self.value)
+  }
+
+  // Splits `range` into the values that pass the test and those that
+  // don't, as non-empty ranges; equality tests can cut out a hole.
+  pub fn split(&self, range: Range<u64>) -> (Vec<Range<u64>>, Vec<Range<u64>>) {
+    let clamp = |value: u64| value.clamp(range.start, range.end);
+    let below = |value| range.start..clamp(value);
+    let above = |value| clamp(value)..range.end;
+    let value = self.value;
+    let next = value.saturating_add(1);
+    let only = clamp(value)..clamp(next);
+
+    let (range_match, range_miss) = match self.operator {
+      RuleOperator::LessThan => (vec![below(value)], vec![above(value)]),
+      RuleOperator::LessOrEqual => (vec![below(next)], vec![above(next)]),
+      RuleOperator::GreaterThan => (vec![above(next)], vec![below(next)]),
+      RuleOperator::GreaterOrEqual => (vec![above(value)], vec![below(value)]),
+      RuleOperator::Equal => (vec![only], vec![below(value), above(next)]),
+      RuleOperator::NotEqual => (vec![below(value), above(next)], vec![only]),
+    };
+
+    let non_empty = |ranges: Vec<Range<u64>>| {
+      ranges
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .collect()
+    };
+
+    (non_empty(range_match), non_empty(range_miss))
+  }
+
+  // Narrows `ranges` down to the values that miss the test, and tells
+  // whether any of them matched.
+  pub fn narrow(&self, ranges: &mut Vec<Range<u64>>) -> bool {
+    let mut matches = false;
+    let mut range_misses = Vec::new();
+
+    for range in ranges.drain(..) {
+      let (range_match, range_miss) = self.split(range);
+      matches |= !range_match.is_empty();
+      range_misses.extend(range_miss);
     }
+
+    *ranges = range_misses;
+    matches
   }
 }
 
-#[derive(Debug)]
-enum RuleOutcome<'a> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleOutcome<'a> {
   Final(bool),
   Workflow(&'a str),
 }
 
-#[derive(Debug)]
-enum Rule<'a> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule<'a> {
   Condition(RuleTest<'a>, RuleOutcome<'a>),
   Fallback(RuleOutcome<'a>),
 }
 
-fn parse_input(input: &str) -> (Workflows, impl Iterator<Item = Part>) {
-  let (workflows, inputs) = input.split_once("\n\n").unwrap();
+impl<'a> Rule<'a> {
+  pub fn outcome(&self) -> &RuleOutcome<'a> {
+    match self {
+      Rule::Condition(_, outcome) | Rule::Fallback(outcome) => outcome,
+    }
+  }
+}
+
+impl Display for RuleOutcome<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RuleOutcome::Final(true) => write!(f, "A"),
+      RuleOutcome::Final(false) => write!(f, "R"),
+      RuleOutcome::Workflow(workflow) => write!(f, "{workflow}"),
+    }
+  }
+}
+
+impl Display for Rule<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Rule::Condition(test, outcome) => write!(
+        f,
+        "{}{}{}:{outcome}",
+        test.category, test.operator, test.value
+      ),
+      Rule::Fallback(outcome) => write!(f, "{outcome}"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic<'a> {
+  MissingEntry,
+  UndefinedWorkflow {
+    workflow: &'a str,
+    rule: usize,
+    target: &'a str,
+  },
+  Cycle {
+    workflows: Vec<&'a str>,
+  },
+  MissingFallback {
+    workflow: &'a str,
+  },
+  ShadowedRule {
+    workflow: &'a str,
+    rule: usize,
+  },
+  UndeclaredCategory {
+    workflow: &'a str,
+    rule: usize,
+    category: &'a str,
+  },
+}
+
+impl Diagnostic<'_> {
+  // Shadowed rules are harmless; everything else would make evaluation
+  // panic or loop forever.
+  pub fn is_error(&self) -> bool {
+    !matches!(self, Diagnostic::ShadowedRule { .. })
+  }
+
+  pub fn to_parse_error(&self, input: &str) -> ParseError {
+    match *self {
+      Diagnostic::MissingEntry => {
+        ParseError::expected(input, &input[..0], "an `in` workflow")
+      }
+      Diagnostic::UndefinedWorkflow { target, .. } => {
+        ParseError::expected(input, target, "a defined workflow")
+      }
+      Diagnostic::Cycle { ref workflows } => ParseError::expected(
+        input,
+        workflows[0],
+        "a workflow that does not lead back to itself",
+      ),
+      Diagnostic::MissingFallback { workflow } => {
+        ParseError::expected(input, workflow, "a workflow ending in a fallback")
+      }
+      Diagnostic::ShadowedRule { workflow, .. } => {
+        ParseError::expected(input, workflow, "reachable rules")
+      }
+      Diagnostic::UndeclaredCategory { category, .. } => {
+        ParseError::expected(input, category, "a declared category")
+      }
+    }
+  }
+}
+
+impl Display for Diagnostic<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Diagnostic::MissingEntry => write!(f, "missing workflow `in`"),
+      Diagnostic::UndefinedWorkflow {
+        workflow,
+        rule,
+        target,
+      } => write!(
+        f,
+        "rule {rule} of `{workflow}` sends parts to undefined workflow `{target}`"
+      ),
+      Diagnostic::Cycle { workflows } => {
+        write!(f, "workflows loop: {} -> {}", workflows.join(" -> "), workflows[0])
+      }
+      Diagnostic::MissingFallback { workflow } => {
+        write!(f, "`{workflow}` has no fallback rule")
+      }
+      Diagnostic::ShadowedRule { workflow, rule } => write!(
+        f,
+        "rule {rule} of `{workflow}` is shadowed by earlier conditions"
+      ),
+      Diagnostic::UndeclaredCategory {
+        workflow,
+        rule,
+        category,
+      } => write!(
+        f,
+        "rule {rule} of `{workflow}` tests undeclared category `{category}`"
+      ),
+    }
+  }
+}
+
+fn find_cycles<'a>(
+  workflows: &Workflows<'a>,
+  workflow: &'a str,
+  stack: &mut Vec<&'a str>,
+  done: &mut HashSet<&'a str>,
+  cycles: &mut Vec<Vec<&'a str>>,
+) {
+  if done.contains(workflow) {
+    return;
+  }
+
+  if let Some(start) = stack.iter().position(|&name| name == workflow) {
+    let mut cycle = stack[start..].to_vec();
+    let min = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+    cycle.rotate_left(min);
+    if !cycles.contains(&cycle) {
+      cycles.push(cycle);
+    }
+    return;
+  }
+
+  let Some(rules) = workflows.get(workflow) else {
+    return;
+  };
+
+  stack.push(workflow);
+
+  for rule in rules {
+    if let RuleOutcome::Workflow(target) = *rule.outcome() {
+      find_cycles(workflows, target, stack, done, cycles);
+    }
+  }
+
+  stack.pop();
+  done.insert(workflow);
+}
+
+pub const XMAS: [&str; 4] = ["x", "m", "a", "s"];
+
+// The categories and rating bounds of the puzzle: `xmas`, from 1 to 4000.
+pub fn xmas_bounds() -> Ranges<'static> {
+  XMAS
+    .into_iter()
+    .map(|category| (category, 1..4001))
+    .collect()
+}
+
+fn has_fallback(rules: &[Rule]) -> bool {
+  matches!(rules.last(), Some(Rule::Fallback(_)))
+}
+
+// Checks that every part entering `in` reaches a final outcome, and points
+// out rules that can never match because of the ones before them.
+pub fn validate<'a>(workflows: &Workflows<'a>) -> Vec<Diagnostic<'a>> {
+  validate_with(workflows, None)
+}
+
+// With declared `bounds`, rules may only test the declared categories, and
+// shadowing takes the bounds into account.
+pub fn validate_with<'a>(
+  workflows: &Workflows<'a>,
+  bounds: Option<&Ranges<'a>>,
+) -> Vec<Diagnostic<'a>> {
+  let mut diagnostics = Vec::new();
+
+  let mut names = workflows.keys().copied().collect::<Vec<_>>();
+  names.sort_unstable();
+
+  if !workflows.contains_key("in") {
+    diagnostics.push(Diagnostic::MissingEntry);
+  }
+
+  for &workflow in &names {
+    let mut remaining = HashMap::<&str, Vec<Range<u64>>>::new();
+
+    for (rule_index, rule) in workflows[workflow].iter().enumerate() {
+      if let RuleOutcome::Workflow(target) = *rule.outcome() {
+        if !workflows.contains_key(target) {
+          diagnostics.push(Diagnostic::UndefinedWorkflow {
+            workflow,
+            rule: rule_index,
+            target,
+          });
+        }
+      }
+
+      let exhausted = remaining.values().any(Vec::is_empty);
+
+      let matches = match rule {
+        Rule::Condition(test, _) => {
+          let bound = match bounds {
+            Some(bounds) => bounds.get(test.category).cloned(),
+            None => Some(UNBOUNDED),
+          };
+
+          let Some(bound) = bound else {
+            diagnostics.push(Diagnostic::UndeclaredCategory {
+              workflow,
+              rule: rule_index,
+              category: test.category,
+            });
+            continue;
+          };
+
+          let ranges = remaining
+            .entry(test.category)
+            .or_insert_with(|| vec![bound]);
+
+          test.narrow(ranges)
+        }
+        Rule::Fallback(_) => true,
+      };
+
+      if exhausted || !matches {
+        diagnostics.push(Diagnostic::ShadowedRule {
+          workflow,
+          rule: rule_index,
+        });
+      }
+    }
+
+    // Conditions that together cover every value need no fallback.
+    let exhausted = remaining.values().any(Vec::is_empty);
+    if !has_fallback(&workflows[workflow]) && !exhausted {
+      diagnostics.push(Diagnostic::MissingFallback { workflow });
+    }
+  }
+
+  let mut cycles = Vec::new();
+  let mut done = HashSet::new();
+
+  for &workflow in &names {
+    find_cycles(workflows, workflow, &mut Vec::new(), &mut done, &mut cycles);
+  }
+
+  diagnostics.extend(
+    cycles
+      .into_iter()
+      .map(|workflows| Diagnostic::Cycle { workflows }),
+  );
+
+  diagnostics
+}
+
+// Fails with every error that `validate_with` finds, leaving only the
+// harmless diagnostics out.
+fn check<'a>(
+  workflows: &Workflows<'a>,
+  bounds: Option<&Ranges<'a>>,
+) -> Result<(), Vec<Diagnostic<'a>>> {
+  let errors = validate_with(workflows, bounds)
+    .into_iter()
+    .filter(Diagnostic::is_error)
+    .collect::<Vec<_>>();
+
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(errors)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+  Accept,
+  Reject,
+  Goto(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+  // Goes to `target` if the rating in `slot` passes, otherwise falls
+  // through to the next instruction.
+  Test {
+    slot: usize,
+    operator: RuleOperator,
+    value: u64,
+    target: Target,
+  },
+  Jump(Target),
+}
+
+// Workflows flattened into a single instruction list, with workflow names
+// resolved to instruction indices and categories to rating slots.
+#[derive(Debug, Clone)]
+pub struct CompiledWorkflows<'a> {
+  pub categories: Vec<&'a str>,
+  pub instructions: Vec<Instruction>,
+  pub entry: usize,
+}
+
+impl<'a> CompiledWorkflows<'a> {
+  pub fn compile(
+    workflows: &Workflows<'a>,
+  ) -> Result<Self, Vec<Diagnostic<'a>>> {
+    Self::compile_with(workflows, None)
+  }
+
+  // Without declared `bounds`, the slots are the categories that the rules
+  // test.
+  pub fn compile_with(
+    workflows: &Workflows<'a>,
+    bounds: Option<&Ranges<'a>>,
+  ) -> Result<Self, Vec<Diagnostic<'a>>> {
+    check(workflows, bounds)?;
+
+    let mut names = workflows.keys().copied().collect::<Vec<_>>();
+    names.sort_unstable();
+
+    let mut starts = HashMap::new();
+    let mut len = 0;
+    for &name in &names {
+      starts.insert(name, len);
+      len +=
+        workflows[name].len() + usize::from(!has_fallback(&workflows[name]));
+    }
+
+    let mut categories = match bounds {
+      Some(bounds) => bounds.keys().copied().collect(),
+      None => workflows
+        .values()
+        .flatten()
+        .filter_map(|rule| match rule {
+          Rule::Condition(test, _) => Some(test.category),
+          Rule::Fallback(_) => None,
+        })
+        .collect::<Vec<_>>(),
+    };
+    categories.sort_unstable();
+    categories.dedup();
+
+    let target = |outcome: &RuleOutcome| match *outcome {
+      RuleOutcome::Final(true) => Target::Accept,
+      RuleOutcome::Final(false) => Target::Reject,
+      RuleOutcome::Workflow(workflow) => Target::Goto(starts[workflow]),
+    };
+
+    // Only parts outside the declared bounds can get past conditions that
+    // cover every value, and they are rejected, as `evaluate` does.
+    let reject_rest = |rules: &[Rule]| {
+      (!has_fallback(rules)).then_some(Instruction::Jump(Target::Reject))
+    };
+
+    let instructions = names
+      .iter()
+      .flat_map(|name| {
+        let rules = &workflows[name];
+        rules
+          .iter()
+          .map(|rule| match rule {
+            Rule::Condition(test, outcome) => Instruction::Test {
+              slot: categories.binary_search(&test.category).unwrap(),
+              operator: test.operator,
+              value: test.value,
+              target: target(outcome),
+            },
+            Rule::Fallback(outcome) => Instruction::Jump(target(outcome)),
+          })
+          .chain(reject_rest(rules))
+      })
+      .collect();
+
+    Ok(Self {
+      entry: starts["in"],
+      categories,
+      instructions,
+    })
+  }
+
+  // The ratings of `part` laid out by slot, if it rates every category the
+  // workflows test.
+  pub fn ratings(&self, part: &Part) -> Option<Vec<u64>> {
+    self
+      .categories
+      .iter()
+      .map(|category| part.get(category).copied())
+      .collect()
+  }
+
+  pub fn accepts(&self, ratings: &[u64]) -> bool {
+    let mut index = self.entry;
+
+    loop {
+      let target = match self.instructions[index] {
+        Instruction::Test {
+          slot,
+          operator,
+          value,
+          target,
+        } => {
+          if !operator.test(ratings[slot], value) {
+            index += 1;
+            continue;
+          }
+          target
+        }
+        Instruction::Jump(target) => target,
+      };
+
+      match target {
+        Target::Accept => return true,
+        Target::Reject => return false,
+        Target::Goto(next) => index = next,
+      }
+    }
+  }
+}
+
+// Walks the workflows by name, as written; slower than the compiled form,
+// but handy for cross-checking it. Parts that no rule of a workflow
+// matches are rejected.
+pub fn evaluate(workflows: &Workflows, part: &Part) -> bool {
+  let mut workflow = "in";
+
+  loop {
+    let outcome = workflows[workflow].iter().find_map(|rule| match rule {
+      Rule::Condition(test, outcome) if test.call(part) => Some(outcome),
+      Rule::Fallback(outcome) => Some(outcome),
+      _ => None,
+    });
+
+    match outcome {
+      Some(RuleOutcome::Final(result)) => return *result,
+      Some(RuleOutcome::Workflow(next_workflow)) => workflow = next_workflow,
+      None => return false,
+    }
+  }
+}
+
+fn parse_outcome(target: &str) -> RuleOutcome {
+  match target {
+    "A" => RuleOutcome::Final(true),
+    "R" => RuleOutcome::Final(false),
+    workflow => RuleOutcome::Workflow(workflow),
+  }
+}
+
+pub fn parse_input(input: &str) -> Result<(Workflows, Vec<Part>), ParseError> {
+  let (workflows, inputs) = input.split_once("\n\n").ok_or_else(|| {
+    ParseError::expected(input, &input[input.len()..], "a blank line")
+  })?;
 
   let workflows = workflows
     .lines()
     .map(|line| {
-      let (workflow, rest) = line.split_once('{').unwrap();
+      let (workflow, rest) = line
+        .split_once('{')
+        .ok_or_else(|| ParseError::expected(input, line, "`{`"))?;
+
       let rules = rest
-        .trim_end_matches('}')
+        .strip_suffix('}')
+        .ok_or_else(|| ParseError::expected(input, rest, "`}`"))?
         .split(',')
         .map(|rule| {
-          if let Some((test, target)) = rule.split_once(':') {
-            let test = if let Some((category, value)) = test.split_once('>') {
-              RuleTest {
-                category,
-                value: value.parse().unwrap(),
-                operator: RuleOperator::GreaterThan,
-              }
-            } else if let Some((category, value)) = test.split_once('<') {
-              RuleTest {
-                category,
-                value: value.parse().unwrap(),
-                operator: RuleOperator::LessThan,
-              }
-            } else {
-              panic!()
-            };
-
-            let outcome = match target {
-              "A" => RuleOutcome::Final(true),
-              "R" => RuleOutcome::Final(false),
-              workflow => RuleOutcome::Workflow(workflow),
-            };
-
-            Rule::Condition(test, outcome)
-          } else {
-            let outcome = match rule {
-              "A" => RuleOutcome::Final(true),
-              "R" => RuleOutcome::Final(false),
-              workflow => RuleOutcome::Workflow(workflow),
-            };
-
-            Rule::Fallback(outcome)
-          }
+          let Some((test, target)) = rule.split_once(':') else {
+            return Ok(Rule::Fallback(parse_outcome(rule)));
+          };
+
+          let Some((category, operator, value)) = RuleOperator::SYMBOLS
+            .iter()
+            .find_map(|&(symbol, operator)| {
+              let (category, value) = test.split_once(symbol)?;
+              Some((category, operator, value))
+            })
+          else {
+            return Err(ParseError::expected(
+              input,
+              test,
+              "a comparison operator",
+            ));
+          };
+
+          let test = RuleTest {
+            category,
+            operator,
+            value: parse_number(input, value)?,
+          };
+
+          Ok(Rule::Condition(test, parse_outcome(target)))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-      (workflow, rules)
+      Ok((workflow, rules))
     })
-    .collect();
-
-  let parts = inputs.lines().map(|line| {
-    line
-      .trim_start_matches('{')
-      .trim_end_matches('}')
-      .split(',')
-      .map(|group| {
-        let (category, value) = group.split_once('=').unwrap();
-        (category, value.parse().unwrap())
-      })
-      .collect()
-  });
+    .collect::<Result<_, ParseError>>()?;
 
-  (workflows, parts)
+  let parts = inputs
+    .lines()
+    .map(|line| {
+      line
+        .strip_prefix('{')
+        .and_then(|line| line.strip_suffix('}'))
+        .ok_or_else(|| ParseError::expected(input, line, "`{...}`"))?
+        .split(',')
+        .map(|group| {
+          let (category, value) = group
+            .split_once('=')
+            .ok_or_else(|| ParseError::expected(input, group, "`=`"))?;
+          Ok((category, parse_number(input, value)?))
+        })
+        .collect()
+    })
+    .collect::<Result<_, ParseError>>()?;
+
+  Ok((workflows, parts))
 }
 
-pub fn part1(input: &str) -> u64 {
-  let (workflows, parts) = parse_input(input);
+pub fn part1(input: &str) -> Result<u64, ParseError> {
+  let (workflows, parts) = parse_input(input)?;
+  let bounds = xmas_bounds();
+  let compiled = CompiledWorkflows::compile_with(&workflows, Some(&bounds))
+    .map_err(|errors| errors[0].to_parse_error(input))?;
 
-  parts
-    .filter(|part| {
-      let mut workflow = "in";
+  let lines = input.split_once("\n\n").map_or("", |(_, parts)| parts);
+  let mut total = 0;
 
-      loop {
-        for rule in workflows.get(workflow).unwrap() {
-          let outcome = match rule {
-            Rule::Condition(test, outcome) if test.call(part) => outcome,
-            Rule::Fallback(outcome) => outcome,
-            _ => continue,
-          };
+  for (line, part) in lines.lines().zip(&parts) {
+    for (&category, rating) in part {
+      if !bounds
+        .get(category)
+        .is_some_and(|range| range.contains(rating))
+      {
+        return Err(ParseError::expected(
+          input,
+          category,
+          "a declared category with a rating in bounds",
+        ));
+      }
+    }
 
-          match outcome {
-            RuleOutcome::Final(result) => {
-              return *result;
-            }
-            RuleOutcome::Workflow(next_workflow) => {
-              workflow = next_workflow;
-              break;
-            }
-          }
-        }
+    let missing =
+      || ParseError::expected(input, line, "a rating for every category");
+
+    if !bounds.keys().all(|category| part.contains_key(category)) {
+      return Err(missing());
+    }
+
+    let ratings = compiled.ratings(part).ok_or_else(missing)?;
+
+    if compiled.accepts(&ratings) {
+      total += part.values().sum::<u64>();
+    }
+  }
+
+  Ok(total)
+}
+
+pub type Ranges<'a> = HashMap<&'a str, Range<u64>>;
+
+// A rule whose outcome was taken on the way to a box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step<'a> {
+  pub workflow: &'a str,
+  pub rule: usize,
+}
+
+impl Display for Step<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}:{}", self.workflow, self.rule)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcceptedBox<'a> {
+  pub ranges: Ranges<'a>,
+  pub path: Vec<Step<'a>>,
+}
+
+impl AcceptedBox<'_> {
+  pub fn volume(&self) -> u64 {
+    self
+      .ranges
+      .values()
+      .map(|range| range.end - range.start)
+      .product()
+  }
+}
+
+fn take_outcome<'a>(
+  workflows: &Workflows<'a>,
+  step: Step<'a>,
+  outcome: &RuleOutcome<'a>,
+  ranges: Ranges<'a>,
+  path: &mut Vec<Step<'a>>,
+  boxes: &mut Vec<AcceptedBox<'a>>,
+) {
+  path.push(step);
+
+  match *outcome {
+    RuleOutcome::Final(true) => boxes.push(AcceptedBox {
+      ranges,
+      path: path.clone(),
+    }),
+    RuleOutcome::Final(false) => {}
+    RuleOutcome::Workflow(next) => {
+      collect_boxes(workflows, next, 0, ranges, path, boxes)
+    }
+  }
+
+  path.pop();
+}
+
+fn collect_boxes<'a>(
+  workflows: &Workflows<'a>,
+  workflow: &'a str,
+  rule_index: usize,
+  ranges: Ranges<'a>,
+  path: &mut Vec<Step<'a>>,
+  boxes: &mut Vec<AcceptedBox<'a>>,
+) {
+  let step = Step {
+    workflow,
+    rule: rule_index,
+  };
+
+  match &workflows[workflow][rule_index] {
+    Rule::Condition(test, outcome) => {
+      let (range_match, range_miss) = test.split(ranges[test.category].clone());
+
+      for range in range_match {
+        let mut ranges = ranges.clone();
+        ranges.insert(test.category, range);
+        take_outcome(workflows, step, outcome, ranges, path, boxes);
       }
-    })
-    .map(|part| part.values().sum::<u64>())
-    .sum()
+
+      for range in range_miss {
+        let mut ranges = ranges.clone();
+        ranges.insert(test.category, range);
+        collect_boxes(workflows, workflow, rule_index + 1, ranges, path, boxes);
+      }
+    }
+    Rule::Fallback(outcome) => {
+      take_outcome(workflows, step, outcome, ranges, path, boxes)
+    }
+  }
+}
+
+// Splits `ranges` into the disjoint boxes of parts that `in` accepts. The
+// workflows are validated against `ranges` first, since evaluating them
+// would panic or recurse forever on errors.
+pub fn accepted_boxes<'a>(
+  workflows: &Workflows<'a>,
+  ranges: Ranges<'a>,
+) -> Result<Vec<AcceptedBox<'a>>, Vec<Diagnostic<'a>>> {
+  check(workflows, Some(&ranges))?;
+  Ok(unchecked_boxes(workflows, ranges))
 }
 
-pub fn part2(input: &str) -> u64 {
-  let (workflows, parts) = parse_input(input);
+// `workflows` must be free of errors for the categories of `ranges`.
+fn unchecked_boxes<'a>(
+  workflows: &Workflows<'a>,
+  ranges: Ranges<'a>,
+) -> Vec<AcceptedBox<'a>> {
+  let mut boxes = Vec::new();
+
+  if ranges.values().all(|range| !range.is_empty()) {
+    collect_boxes(workflows, "in", 0, ranges, &mut Vec::new(), &mut boxes);
+  }
+
+  boxes
+}
+
+// One row per box with inclusive bounds for every category, followed by
+// the volume and the path that led there.
+pub fn boxes_to_csv(boxes: &[AcceptedBox]) -> String {
+  let mut categories = boxes
+    .iter()
+    .flat_map(|accepted| accepted.ranges.keys().copied())
+    .collect::<Vec<_>>();
+  categories.sort_unstable();
+  categories.dedup();
+
+  let mut csv = String::new();
 
-  let mut categories = HashSet::new();
-  for part in parts {
-    for &key in part.keys() {
-      categories.insert(key);
+  for category in &categories {
+    csv += &format!("{category}_min,{category}_max,");
+  }
+  csv += "volume,path\n";
+
+  for accepted in boxes {
+    for category in &categories {
+      let range = &accepted.ranges[category];
+      csv += &format!("{},{},", range.start, range.end - 1);
     }
+
+    let path = accepted
+      .path
+      .iter()
+      .map(Step::to_string)
+      .collect::<Vec<_>>();
+    csv += &format!("{},{}\n", accepted.volume(), path.join(">"));
   }
 
-  type Ranges<'a> = HashMap<&'a str, Range<u64>>;
+  csv
+}
 
-  let ranges = categories
-    .into_iter()
-    .map(|category| (category, 1..4001))
-    .collect();
+// Drops conditions that can never match, turns conditions that always
+// match into fallbacks, and drops conditions just before the fallback that
+// lead to the same outcome.
+fn simplify_rules(rules: &mut Workflow, bounds: &Ranges) {
+  let mut remaining = HashMap::<&str, Vec<Range<u64>>>::new();
+  let mut simplified = Vec::new();
 
-  fn process_outcome(
-    ranges: Ranges,
-    workflows: &Workflows,
-    outcome: &RuleOutcome,
-  ) -> u64 {
-    match outcome {
-      RuleOutcome::Final(true) => ranges
-        .into_values()
-        .map(|range| range.end - range.start)
-        .product(),
-      RuleOutcome::Final(false) => 0,
-      RuleOutcome::Workflow(workflow_name) => accepted_combinations(
-        ranges,
-        workflows,
-        workflows[workflow_name].iter(),
-      ),
+  for rule in rules.drain(..) {
+    let Rule::Condition(test, outcome) = rule else {
+      simplified.push(rule);
+      break;
+    };
+
+    let ranges = remaining
+      .entry(test.category)
+      .or_insert_with(|| vec![bounds[test.category].clone()]);
+    let matches = test.narrow(ranges);
+
+    if ranges.is_empty() {
+      simplified.push(Rule::Fallback(outcome));
+      break;
+    }
+
+    if matches {
+      simplified.push(Rule::Condition(test, outcome));
     }
   }
 
-  fn accepted_combinations<'a>(
-    ranges: Ranges<'a>,
-    workflows: &Workflows,
-    mut rule_iter: impl Iterator<Item = &'a Rule<'a>>,
-  ) -> u64 {
-    match rule_iter.next().unwrap() {
-      Rule::Condition(test, outcome) => {
-        let range = ranges[test.category].clone();
-
-        let (range_match, range_miss) = match test.operator {
-          RuleOperator::LessThan => {
-            if range.end < test.value {
-              (Some(range), None)
-            } else if range.start >= test.value {
-              (None, Some(range))
-            } else {
-              (Some(range.start..test.value), Some(test.value..range.end))
-            }
-          }
-          RuleOperator::GreaterThan => {
-            if range.start > test.value {
-              (Some(range), None)
-            } else if range.end <= test.value {
-              (None, Some(range))
-            } else {
-              (
-                Some(test.value + 1..range.end),
-                Some(range.start..test.value + 1),
-              )
-            }
-          }
-        };
+  while let [.., Rule::Condition(_, outcome), Rule::Fallback(fallback)] =
+    simplified.as_slice()
+  {
+    if outcome != fallback {
+      break;
+    }
 
-        let mut sum = 0;
+    simplified.remove(simplified.len() - 2);
+  }
 
-        if let Some(range) = range_match {
-          let mut ranges = ranges.clone();
-          ranges.insert(test.category, range);
-          sum += process_outcome(ranges, workflows, outcome)
-        }
+  *rules = simplified;
+}
 
-        if let Some(range) = range_miss {
-          let mut ranges = ranges;
-          ranges.insert(test.category, range);
-          sum += accepted_combinations(ranges, workflows, rule_iter)
-        }
+fn outcomes_mut<'a, 'b>(
+  workflows: &'b mut Workflows<'a>,
+) -> impl Iterator<Item = &'b mut RuleOutcome<'a>> {
+  workflows.values_mut().flatten().map(|rule| match rule {
+    Rule::Condition(_, outcome) | Rule::Fallback(outcome) => outcome,
+  })
+}
+
+// Replaces references to workflows that are just a fallback with the
+// fallback's outcome.
+fn fold_constants(workflows: &mut Workflows) {
+  let constants = workflows
+    .iter()
+    .filter(|&(&name, _)| name != "in")
+    .filter_map(|(&name, rules)| match rules.as_slice() {
+      [Rule::Fallback(outcome)] if *outcome != RuleOutcome::Workflow(name) => {
+        Some((name, *outcome))
+      }
+      _ => None,
+    })
+    .collect::<HashMap<_, _>>();
 
-        sum
+  for outcome in outcomes_mut(workflows) {
+    if let RuleOutcome::Workflow(name) = *outcome {
+      if let Some(&constant) = constants.get(name) {
+        *outcome = constant;
       }
+    }
+  }
+}
+
+// Only workflows reached through a fallback can be inlined: their rules
+// simply take the fallback's place, where a condition would have to be
+// combined with each of their rules.
+fn inline_single_use(workflows: &mut Workflows) {
+  loop {
+    let mut uses = HashMap::<&str, usize>::new();
+    for outcome in outcomes_mut(workflows) {
+      if let RuleOutcome::Workflow(name) = *outcome {
+        *uses.entry(name).or_default() += 1;
+      }
+    }
+
+    let inlined = workflows.iter().find_map(|(&parent, rules)| {
+      let Some(&Rule::Fallback(RuleOutcome::Workflow(child))) = rules.last()
+      else {
+        return None;
+      };
+
+      (child != parent
+        && child != "in"
+        && uses[child] == 1
+        && workflows.contains_key(child))
+      .then_some((parent, child))
+    });
+
+    let Some((parent, child)) = inlined else {
+      return;
+    };
+
+    let child_rules = workflows.remove(child).unwrap();
+    let parent_rules = workflows.get_mut(parent).unwrap();
+    parent_rules.pop();
+    parent_rules.extend(child_rules);
+  }
+}
+
+fn remove_unreachable(workflows: &mut Workflows) {
+  if !workflows.contains_key("in") {
+    return;
+  }
+
+  let mut reachable = HashSet::from(["in"]);
+  let mut queue = vec!["in"];
+
+  while let Some(workflow) = queue.pop() {
+    for rule in workflows.get(workflow).into_iter().flatten() {
+      if let RuleOutcome::Workflow(next) = *rule.outcome() {
+        if reachable.insert(next) {
+          queue.push(next);
+        }
+      }
+    }
+  }
+
+  workflows.retain(|name, _| reachable.contains(name));
+}
+
+// Rewrites `workflows` into a smaller set that accepts the same parts
+// within `bounds`; conditions that only matter outside them are dropped.
+pub fn minimize<'a>(
+  workflows: &Workflows<'a>,
+  bounds: &Ranges<'a>,
+) -> Result<Workflows<'a>, Vec<Diagnostic<'a>>> {
+  check(workflows, Some(bounds))?;
+
+  let mut workflows = workflows.clone();
+
+  loop {
+    let before = workflows.clone();
+
+    for rules in workflows.values_mut() {
+      simplify_rules(rules, bounds);
+    }
+
+    fold_constants(&mut workflows);
+    inline_single_use(&mut workflows);
+    remove_unreachable(&mut workflows);
+
+    if workflows == before {
+      return Ok(workflows);
+    }
+  }
+}
+
+fn negate<'a>(workflows: &Workflows<'a>) -> Workflows<'a> {
+  let mut negated = workflows.clone();
 
-      Rule::Fallback(outcome) => process_outcome(ranges, workflows, outcome),
+  for outcome in outcomes_mut(&mut negated) {
+    if let RuleOutcome::Final(accepted) = outcome {
+      *accepted = !*accepted;
     }
   }
 
-  accepted_combinations(ranges, &workflows, workflows["in"].iter())
+  negated
+}
+
+// A part within `bounds` that one set of workflows accepts and the other
+// rejects. Each box that `a` accepts is run through `b` with its outcomes
+// flipped, which finds the parts of the box that `b` rejects.
+pub fn counterexample<'a>(
+  a: &Workflows<'a>,
+  b: &Workflows<'a>,
+  bounds: &Ranges<'a>,
+) -> Result<Option<Part<'a>>, Vec<Diagnostic<'a>>> {
+  check(a, Some(bounds))?;
+  check(b, Some(bounds))?;
+
+  let part = [(a, b), (b, a)]
+    .into_iter()
+    .find_map(|(accepting, rejecting)| {
+      let rejecting = negate(rejecting);
+
+      unchecked_boxes(accepting, bounds.clone())
+        .into_iter()
+        .flat_map(|accepted| unchecked_boxes(&rejecting, accepted.ranges))
+        .map(|rejected| {
+          rejected
+            .ranges
+            .iter()
+            .map(|(&category, range)| (category, range.start))
+            .collect()
+        })
+        .next()
+    });
+
+  Ok(part)
+}
+
+pub fn equivalent<'a>(
+  a: &Workflows<'a>,
+  b: &Workflows<'a>,
+  bounds: &Ranges<'a>,
+) -> Result<bool, Vec<Diagnostic<'a>>> {
+  Ok(counterexample(a, b, bounds)?.is_none())
+}
+
+// The workflows section of the puzzle input, one workflow per line in name
+// order.
+pub fn workflows_to_text(workflows: &Workflows) -> String {
+  let mut names = workflows.keys().copied().collect::<Vec<_>>();
+  names.sort_unstable();
+
+  let mut text = String::new();
+
+  for name in names {
+    let rules = workflows[name]
+      .iter()
+      .map(Rule::to_string)
+      .collect::<Vec<_>>();
+    text += &format!("{name}{{{}}}\n", rules.join(","));
+  }
+
+  text
+}
+
+pub fn part2(input: &str) -> Result<u64, ParseError> {
+  let (workflows, _) = parse_input(input)?;
+  let bounds = xmas_bounds();
+  let boxes = accepted_boxes(&workflows, bounds)
+    .map_err(|errors| errors[0].to_parse_error(input))?;
+
+  Ok(boxes.iter().map(AcceptedBox::volume).sum())
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+  const DAY: u8 = 19;
+
+  type Part1 = u64;
+  type Part2 = u64;
+
+  fn parse(input: &str) -> Result<(), ParseError> {
+    parse_input(input).map(drop)
+  }
+
+  fn part1(input: &str) -> Result<u64, ParseError> {
+    part1(input)
+  }
+
+  fn part2(input: &str) -> Result<u64, ParseError> {
+    part2(input)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::utils::ParseErrorKind;
 
   const INPUT_TEST: &str = include_str!("input_test.txt");
   const INPUT: &str = include_str!("input.txt");
 
   #[test]
   fn part1_works() {
-    assert_eq!(part1(INPUT_TEST), 19_114);
-    assert_eq!(part1(INPUT), 373_302);
+    assert_eq!(part1(INPUT_TEST), Ok(19_114));
+    assert_eq!(part1(INPUT), Ok(373_302));
   }
 
   #[test]
   fn part2_works() {
-    assert_eq!(part2(INPUT_TEST), 167_409_079_868_000);
-    assert_eq!(part2(INPUT), 130_262_715_574_114);
+    assert_eq!(part2(INPUT_TEST), Ok(167_409_079_868_000));
+    assert_eq!(part2(INPUT), Ok(130_262_715_574_114));
+  }
+
+  #[test]
+  fn validates_workflows() {
+    let (workflows, _) = parse_input(INPUT).unwrap();
+    assert!(!validate(&workflows).iter().any(Diagnostic::is_error));
+
+    let input = "in{x<10:a,x<5:R,b}\n\
+                 a{m>5:b,m<3:c,m<6:R,A}\n\
+                 b{s>100:in,A}\n\
+                 \n\
+                 {x=1,m=2,a=3,s=4}\n";
+    let (workflows, _) = parse_input(input).unwrap();
+    let diagnostics = validate(&workflows);
+
+    assert_eq!(
+      diagnostics,
+      [
+        Diagnostic::UndefinedWorkflow {
+          workflow: "a",
+          rule: 1,
+          target: "c",
+        },
+        Diagnostic::ShadowedRule {
+          workflow: "a",
+          rule: 3,
+        },
+        Diagnostic::ShadowedRule {
+          workflow: "in",
+          rule: 1,
+        },
+        Diagnostic::Cycle {
+          workflows: vec!["a", "b", "in"],
+        },
+        Diagnostic::Cycle {
+          workflows: vec!["b", "in"],
+        },
+      ]
+    );
+    assert_eq!(
+      diagnostics[3].to_string(),
+      "workflows loop: a -> b -> in -> a"
+    );
+
+    let err = part1(input).unwrap_err();
+    assert_eq!((err.line, err.column), (2, 13));
+    assert_eq!(err.snippet, "c");
+
+    let err = part2("px{a<2006:A,R}\n\n{a=1}\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::Expected("an `in` workflow"));
+  }
+
+  #[test]
+  fn compiled_workflows_match_interpreter() {
+    let (workflows, parts) = parse_input(INPUT).unwrap();
+    let compiled = CompiledWorkflows::compile(&workflows).unwrap();
+
+    assert_eq!(compiled.categories, ["a", "m", "s", "x"]);
+    assert_eq!(
+      compiled.instructions.len(),
+      workflows.values().map(Vec::len).sum::<usize>()
+    );
+
+    let mut seed = 0x2545_f491_u64;
+    let mut rating = || {
+      seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+      (seed >> 33) % 4000 + 1
+    };
+
+    let random_parts = (0..10_000).map(|_| {
+      compiled
+        .categories
+        .iter()
+        .map(|&category| (category, rating()))
+        .collect::<Part>()
+    });
+
+    for part in parts.into_iter().chain(random_parts) {
+      assert_eq!(
+        compiled.accepts(&compiled.ratings(&part).unwrap()),
+        evaluate(&workflows, &part)
+      );
+    }
+
+    let (workflows, _) = parse_input("in{x<5:A}\n\n{x=1}\n").unwrap();
+    assert_eq!(
+      CompiledWorkflows::compile(&workflows).unwrap_err(),
+      [Diagnostic::MissingFallback { workflow: "in" }]
+    );
+  }
+
+  #[test]
+  fn enumerates_accepted_boxes() {
+    let (workflows, _) = parse_input(INPUT_TEST).unwrap();
+    let ranges = ["x", "m", "a", "s"].map(|category| (category, 1..4001));
+    let boxes =
+      accepted_boxes(&workflows, ranges.into_iter().collect()).unwrap();
+
+    assert_eq!(
+      boxes.iter().map(AcceptedBox::volume).sum::<u64>(),
+      167_409_079_868_000
+    );
+
+    for (i, a) in boxes.iter().enumerate() {
+      assert_eq!(a.path[0].workflow, "in");
+
+      let corner = a
+        .ranges
+        .iter()
+        .map(|(&category, range)| (category, range.end - 1))
+        .collect::<Part>();
+      assert!(evaluate(&workflows, &corner));
+
+      for b in &boxes[i + 1..] {
+        assert!(a.ranges.iter().any(|(category, range)| {
+          let other = &b.ranges[category];
+          range.end <= other.start || other.end <= range.start
+        }));
+      }
+    }
+
+    let (workflows, _) =
+      parse_input("in{x<10:ab,R}\nab{m>5:A,R}\n\n{x=1,m=1}\n").unwrap();
+    let ranges = [("x", 1..21), ("m", 1..11)].into_iter().collect();
+    let boxes = accepted_boxes(&workflows, ranges).unwrap();
+
+    assert_eq!(
+      boxes[0].path,
+      [
+        Step {
+          workflow: "in",
+          rule: 0
+        },
+        Step {
+          workflow: "ab",
+          rule: 0
+        },
+      ]
+    );
+    assert_eq!(
+      boxes_to_csv(&boxes),
+      "m_min,m_max,x_min,x_max,volume,path\n\
+       6,10,1,9,45,in:0>ab:0\n"
+    );
+
+    let invalid = |input: &'static str| {
+      let (workflows, _) = parse_input(input).unwrap();
+      accepted_boxes(&workflows, xmas_bounds()).unwrap_err()
+    };
+
+    assert_eq!(
+      invalid("in{x<5:A}\n\n"),
+      [Diagnostic::MissingFallback { workflow: "in" }]
+    );
+    assert_eq!(
+      invalid("in{z<5:A,R}\n\n"),
+      [Diagnostic::UndeclaredCategory {
+        workflow: "in",
+        rule: 0,
+        category: "z",
+      }]
+    );
+    assert_eq!(
+      invalid("in{x<5:ab,R}\nab{in}\n\n"),
+      [Diagnostic::Cycle {
+        workflows: vec!["ab", "in"],
+      }]
+    );
+  }
+
+  #[test]
+  fn supports_declared_bounds() {
+    let input = "in{x!=3:lo,R}\n\
+                 lo{m<=4:A,m>=8:A,a==2:R,A}\n\
+                 \n\
+                 {x=3,m=5,a=2}\n";
+    let (workflows, _) = parse_input(input).unwrap();
+    let bounds = [("x", 0..10), ("m", 0..10), ("a", 0..3)]
+      .into_iter()
+      .collect::<Ranges>();
+
+    assert_eq!(validate_with(&workflows, Some(&bounds)), []);
+
+    let compiled =
+      CompiledWorkflows::compile_with(&workflows, Some(&bounds)).unwrap();
+    assert_eq!(compiled.categories, ["a", "m", "x"]);
+
+    let mut accepted = 0;
+    for ratings in (0..3)
+      .flat_map(|a| (0..10).flat_map(move |m| (0..10).map(move |x| [a, m, x])))
+    {
+      let part = compiled.categories.iter().copied().zip(ratings).collect();
+      assert_eq!(compiled.accepts(&ratings), evaluate(&workflows, &part));
+      accepted += u64::from(compiled.accepts(&ratings));
+    }
+
+    let boxes = accepted_boxes(&workflows, bounds.clone()).unwrap();
+    assert_eq!(boxes.iter().map(AcceptedBox::volume).sum::<u64>(), 243);
+    assert_eq!(accepted, 243);
+
+    let (workflows, _) = parse_input("in{s>1:A,a>5:R,A}\n\n{s=2}\n").unwrap();
+    assert_eq!(
+      validate_with(&workflows, Some(&bounds)),
+      [
+        Diagnostic::UndeclaredCategory {
+          workflow: "in",
+          rule: 0,
+          category: "s",
+        },
+        Diagnostic::ShadowedRule {
+          workflow: "in",
+          rule: 1,
+        },
+      ]
+    );
+
+    let input = "in{x==1:A,x!=1:R}\n\n{x=1,m=2,a=3,s=4}\n{x=2,m=2,a=3,s=4}\n";
+    let (workflows, _) = parse_input(input).unwrap();
+    assert_eq!(validate(&workflows), []);
+    assert_eq!(part1(input), Ok(10));
+
+    let (workflows, _) = parse_input("in{x>0:A}\n\n").unwrap();
+    let bounds = xmas_bounds();
+    assert!(validate(&workflows)
+      .contains(&Diagnostic::MissingFallback { workflow: "in" }));
+    assert_eq!(validate_with(&workflows, Some(&bounds)), []);
+
+    let compiled =
+      CompiledWorkflows::compile_with(&workflows, Some(&bounds)).unwrap();
+    let part = Part::from([("x", 0), ("m", 1), ("a", 1), ("s", 1)]);
+    assert!(!evaluate(&workflows, &part));
+    assert!(!compiled.accepts(&compiled.ratings(&part).unwrap()));
+    assert_eq!(compiled.ratings(&Part::from([("m", 1)])), None);
+
+    let err = part1("in{x>0:A}\n\n{x=0,m=1,a=1,s=1}\n").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 2));
+
+    let err = part1("in{s<5:R,A}\n\n{x=1,m=2,a=3}\n").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 1));
+    assert_eq!(
+      err.kind,
+      ParseErrorKind::Expected("a rating for every category")
+    );
+
+    let err = part2("in{z>5:A,R}\n\n{x=1}\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::Expected("a declared category"));
+    assert_eq!((err.line, err.column), (1, 4));
+  }
+
+  #[test]
+  fn minimizes_workflows() {
+    let input = "in{s<1351:px,qqz}\n\
+                 px{a<2006:qkq,m>2090:A,rfg}\n\
+                 qqz{s>2770:A,s>=2771:R,m<1801:hdj,R}\n\
+                 qkq{x<1416:A,crn}\n\
+                 crn{x>2662:A,R}\n\
+                 rfg{s<537:gd,x>2440:R,A}\n\
+                 gd{a>3333:R,R}\n\
+                 hdj{m>838:A,pv}\n\
+                 pv{a>1716:R,A}\n\
+                 lnx{m>1548:A,A}\n\
+                 \n\
+                 {x=787,m=2655,a=1222,s=2876}\n";
+    let (workflows, _) = parse_input(input).unwrap();
+    let bounds = xmas_bounds();
+    let minimized = minimize(&workflows, &bounds).unwrap();
+
+    assert_eq!(
+      workflows_to_text(&minimized),
+      "hdj{m>838:A,a>1716:R,A}\n\
+       in{s<1351:px,s>2770:A,m<1801:hdj,R}\n\
+       px{a<2006:qkq,m>2090:A,s<537:R,x>2440:R,A}\n\
+       qkq{x<1416:A,x>2662:A,R}\n"
+    );
+
+    assert_eq!(equivalent(&workflows, &minimized, &bounds), Ok(true));
+    assert_eq!(
+      accepted_boxes(&minimized, bounds.clone())
+        .unwrap()
+        .iter()
+        .map(AcceptedBox::volume)
+        .sum::<u64>(),
+      167_409_079_868_000
+    );
+
+    let text = workflows_to_text(&minimized) + "\n";
+    let (reparsed, _) = parse_input(&text).unwrap();
+    assert_eq!(reparsed, minimized);
+
+    let (workflows, _) = parse_input(INPUT).unwrap();
+    let minimized = minimize(&workflows, &bounds).unwrap();
+    assert!(minimized.len() < workflows.len());
+    assert_eq!(equivalent(&workflows, &minimized, &bounds), Ok(true));
+
+    let (changed, _) = parse_input("in{x<1416:A,R}\n\n").unwrap();
+    let part = counterexample(&minimized, &changed, &bounds)
+      .unwrap()
+      .unwrap();
+    assert_ne!(evaluate(&minimized, &part), evaluate(&changed, &part));
+  }
+
+  #[test]
+  fn minimizes_within_bounds() {
+    let bounds = xmas_bounds();
+    let (workflows, _) = parse_input("in{x>0:A,R}\n\n").unwrap();
+    let minimized = minimize(&workflows, &bounds).unwrap();
+    assert_eq!(workflows_to_text(&minimized), "in{A}\n");
+  }
+
+  #[test]
+  fn rejects_invalid_workflows_when_minimizing() {
+    let bounds = xmas_bounds();
+    let (valid, _) = parse_input("in{A}\n\n").unwrap();
+    for input in ["in{x<5:A}\n\n", "in{z<5:A,R}\n\n"] {
+      let (workflows, _) = parse_input(input).unwrap();
+      assert!(minimize(&workflows, &bounds).is_err());
+      assert!(counterexample(&workflows, &valid, &bounds).is_err());
+      assert!(counterexample(&valid, &workflows, &bounds).is_err());
+      assert!(equivalent(&workflows, &valid, &bounds).is_err());
+    }
+  }
+
+  #[test]
+  fn reports_invalid_rules() {
+    let err = part1("in{x=10:A,R}\n\n{x=1}\n").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 4));
+    assert_eq!(err.snippet, "x=10");
+    assert_eq!(err.kind, ParseErrorKind::Expected("a comparison operator"));
   }
 }
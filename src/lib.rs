@@ -1,3 +1,7 @@
+use std::path::PathBuf;
+
+mod solution;
+mod timing;
 pub mod utils;
 
 pub mod day00;
@@ -20,3 +24,59 @@ pub mod day16;
 pub mod day17;
 pub mod day18;
 pub mod day19;
+
+pub use solution::{Answer, DaySolver, PartFn, Solution};
+pub use timing::{BenchError, TimingTable, Timings};
+pub use utils::ParseError;
+
+pub static DAYS: &[DaySolver] = &[
+  DaySolver::new::<day01::Day01>(),
+  DaySolver::new::<day02::Day02>(),
+  DaySolver::new::<day03::Day03>(),
+  DaySolver::new::<day04::Day04>(),
+  DaySolver::new::<day05::Day05>(),
+  DaySolver::new::<day06::Day06>(),
+  DaySolver::new::<day07::Day07>(),
+  DaySolver::new::<day08::Day08>(),
+  DaySolver::new::<day09::Day09>(),
+  DaySolver::new::<day10::Day10>(),
+  DaySolver::new::<day11::Day11>(),
+  DaySolver::new::<day12::Day12>(),
+  DaySolver::new::<day13::Day13>(),
+  DaySolver::new::<day14::Day14>(),
+  DaySolver::new::<day15::Day15>(),
+  DaySolver::new::<day16::Day16>(),
+  DaySolver::new::<day17::Day17>(),
+  DaySolver::new::<day18::Day18>(),
+  DaySolver::new::<day19::Day19>(),
+];
+
+pub fn day(day: u8) -> Option<&'static DaySolver> {
+  DAYS.iter().find(|solver| solver.day == day)
+}
+
+pub fn input_path(day: u8) -> PathBuf {
+  [env!("CARGO_MANIFEST_DIR"), "src", &format!("day{day:02}"), "input.txt"]
+    .iter()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn days_are_registered_in_order() {
+    assert!(DAYS.iter().map(|solver| solver.day).eq(1..=19));
+  }
+
+  #[test]
+  fn day_dispatches_to_solution() {
+    let solver = day(11).unwrap();
+    let input = include_str!("day11/input_test.txt");
+    assert_eq!(solver.solve(1, input), Some(Ok(Answer::Signed(374))));
+    assert_eq!(solver.solve(3, input), None);
+    assert!(solver.part(2).is_some() && solver.part(0).is_none());
+    assert!(day(20).is_none());
+  }
+}
@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+use crate::utils::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+  Signed(i64),
+  Unsigned(u64),
+}
+
+macro_rules! impl_from {
+  ($variant:ident: $target:ty => $($source:ty),*) => {
+    $(
+      impl From<$source> for Answer {
+        fn from(value: $source) -> Self {
+          Self::$variant(value as $target)
+        }
+      }
+    )*
+  };
+}
+
+impl_from!(Signed: i64 => i32, i64);
+impl_from!(Unsigned: u64 => u32, u64, usize);
+
+impl Display for Answer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match *self {
+      Self::Signed(value) => write!(f, "{value}"),
+      Self::Unsigned(value) => write!(f, "{value}"),
+    }
+  }
+}
+
+pub trait Solution {
+  const DAY: u8;
+
+  type Part1: Into<Answer>;
+  type Part2: Into<Answer>;
+
+  // Runs only the parsing stage that both parts share, so that it can be
+  // timed separately. Every registered day overrides the default.
+  fn parse(_input: &str) -> Result<(), ParseError> {
+    Ok(())
+  }
+
+  fn part1(input: &str) -> Result<Self::Part1, ParseError>;
+  fn part2(input: &str) -> Result<Self::Part2, ParseError>;
+}
+
+pub type PartFn = fn(&str) -> Result<Answer, ParseError>;
+
+#[derive(Clone, Copy)]
+pub struct DaySolver {
+  pub day: u8,
+  pub parse: fn(&str) -> Result<(), ParseError>,
+  pub part1: PartFn,
+  pub part2: PartFn,
+}
+
+impl DaySolver {
+  pub const fn new<S: Solution>() -> Self {
+    fn part1<S: Solution>(input: &str) -> Result<Answer, ParseError> {
+      S::part1(input).map(Into::into)
+    }
+
+    fn part2<S: Solution>(input: &str) -> Result<Answer, ParseError> {
+      S::part2(input).map(Into::into)
+    }
+
+    Self {
+      day: S::DAY,
+      parse: S::parse,
+      part1: part1::<S>,
+      part2: part2::<S>,
+    }
+  }
+
+  pub fn part(&self, part: u8) -> Option<PartFn> {
+    match part {
+      1 => Some(self.part1),
+      2 => Some(self.part2),
+      _ => None,
+    }
+  }
+
+  pub fn solve(
+    &self,
+    part: u8,
+    input: &str,
+  ) -> Option<Result<Answer, ParseError>> {
+    self.part(part).map(|solve| solve(input))
+  }
+}
@@ -0,0 +1,182 @@
+use std::{
+  fmt::Display,
+  fs,
+  hint::black_box,
+  io,
+  time::{Duration, Instant},
+};
+
+use crate::{input_path, Answer, DaySolver, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+  pub day: u8,
+  pub parse: Duration,
+  pub part1: Duration,
+  pub part2: Duration,
+}
+
+fn average<T>(
+  runs: u32,
+  mut f: impl FnMut() -> Result<T, ParseError>,
+) -> Result<Duration, ParseError> {
+  let start = Instant::now();
+
+  for _ in 0..runs {
+    black_box(f()?);
+  }
+
+  Ok(start.elapsed() / runs)
+}
+
+#[derive(Debug)]
+pub enum BenchError {
+  Input { day: u8, error: io::Error },
+  Parse { day: u8, error: ParseError },
+}
+
+impl Display for BenchError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Input { day, error } => {
+        write!(f, "Failed to read input for day {day}: {error}")
+      }
+      Self::Parse { day, error } => {
+        write!(f, "Invalid input for day {day}: {error}")
+      }
+    }
+  }
+}
+
+impl Timings {
+  // Each part parses its own input, so the part timings include the parse
+  // time; it is reported separately to show how much of them it takes.
+  pub fn measure(
+    solver: &DaySolver,
+    input: &str,
+    runs: u32,
+  ) -> Result<Self, ParseError> {
+    let runs = runs.max(1);
+
+    let parse = average(runs, || (solver.parse)(black_box(input)))?;
+    let part1 = average::<Answer>(runs, || (solver.part1)(black_box(input)))?;
+    let part2 = average::<Answer>(runs, || (solver.part2)(black_box(input)))?;
+
+    Ok(Self {
+      day: solver.day,
+      parse,
+      part1,
+      part2,
+    })
+  }
+
+  // Measures each day against its bundled input.
+  pub fn measure_days<'a>(
+    solvers: impl IntoIterator<Item = &'a DaySolver>,
+    runs: u32,
+  ) -> Result<Vec<Self>, BenchError> {
+    solvers
+      .into_iter()
+      .map(|solver| {
+        let day = solver.day;
+        let input = fs::read_to_string(input_path(day))
+          .map_err(|error| BenchError::Input { day, error })?;
+        Self::measure(solver, &input, runs)
+          .map_err(|error| BenchError::Parse { day, error })
+      })
+      .collect()
+  }
+
+  // Solving both parts, each of which parses the input on its own.
+  pub fn total(&self) -> Duration {
+    self.part1 + self.part2
+  }
+
+  // Solving both parts once the input is parsed.
+  pub fn solve(&self) -> Duration {
+    self.total().saturating_sub(self.parse * 2)
+  }
+}
+
+pub struct TimingTable<'a>(pub &'a [Timings]);
+
+fn write_row(
+  f: &mut std::fmt::Formatter<'_>,
+  label: &str,
+  durations: [Duration; 5],
+) -> std::fmt::Result {
+  write!(f, "{label:<5}")?;
+
+  for duration in durations {
+    write!(f, " {:>10}", format!("{duration:.1?}"))?;
+  }
+
+  writeln!(f)
+}
+
+impl Display for TimingTable<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(
+      f,
+      "{:<5} {:>10} {:>10} {:>10} {:>10} {:>10}",
+      "Day", "Parse", "Part 1", "Part 2", "Solve", "Total"
+    )?;
+
+    let mut sums = [Duration::ZERO; 5];
+
+    for timings in self.0 {
+      let durations = [
+        timings.parse,
+        timings.part1,
+        timings.part2,
+        timings.solve(),
+        timings.total(),
+      ];
+
+      for (sum, duration) in sums.iter_mut().zip(durations) {
+        *sum += duration;
+      }
+
+      write_row(f, &format!("{:02}", timings.day), durations)?;
+    }
+
+    write_row(f, "All", sums)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn renders_timing_table() {
+    let timings = [1, 2].map(|day| Timings {
+      day,
+      parse: Duration::from_micros(5),
+      part1: Duration::from_micros(10),
+      part2: Duration::from_millis(2),
+    });
+
+    assert_eq!(
+      TimingTable(&timings).to_string(),
+      "Day        Parse     Part 1     Part 2      Solve      Total\n\
+       01         5.0µs     10.0µs      2.0ms      2.0ms      2.0ms\n\
+       02         5.0µs     10.0µs      2.0ms      2.0ms      2.0ms\n\
+       All       10.0µs     20.0µs      4.0ms      4.0ms      4.0ms\n"
+    );
+  }
+
+  #[test]
+  fn measures_parse_separately() {
+    let solver = crate::day(17).unwrap();
+    let input = include_str!("day17/input_test.txt");
+
+    let timings = Timings::measure(solver, input, 1).unwrap();
+    assert_eq!(timings.day, 17);
+    assert!(timings.parse > Duration::ZERO);
+    assert!(timings.part1 > timings.parse);
+    assert!(timings.solve() < timings.total());
+
+    assert!(Timings::measure(solver, "12\n3x\n", 1).is_err());
+  }
+}
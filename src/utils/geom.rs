@@ -0,0 +1,271 @@
+use std::{
+  fmt::Display,
+  ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use num::Signed;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point<T> {
+  pub x: T,
+  pub y: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Vector<T> {
+  pub x: T,
+  pub y: T,
+}
+
+impl<T> Point<T> {
+  pub const fn new(x: T, y: T) -> Self {
+    Self { x, y }
+  }
+}
+
+impl<T> Vector<T> {
+  pub const fn new(x: T, y: T) -> Self {
+    Self { x, y }
+  }
+}
+
+impl<T> From<[T; 2]> for Point<T> {
+  fn from([x, y]: [T; 2]) -> Self {
+    Self { x, y }
+  }
+}
+
+impl<T> From<Point<T>> for [T; 2] {
+  fn from(Point { x, y }: Point<T>) -> Self {
+    [x, y]
+  }
+}
+
+impl<T> From<[T; 2]> for Vector<T> {
+  fn from([x, y]: [T; 2]) -> Self {
+    Self { x, y }
+  }
+}
+
+impl<T> From<Vector<T>> for [T; 2] {
+  fn from(Vector { x, y }: Vector<T>) -> Self {
+    [x, y]
+  }
+}
+
+impl<T: Display> Display for Point<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "({}, {})", self.x, self.y)
+  }
+}
+
+impl<T: Add<Output = T>> Add<Vector<T>> for Point<T> {
+  type Output = Self;
+
+  fn add(self, rhs: Vector<T>) -> Self::Output {
+    Self::new(self.x + rhs.x, self.y + rhs.y)
+  }
+}
+
+impl<T: Sub<Output = T>> Sub<Vector<T>> for Point<T> {
+  type Output = Self;
+
+  fn sub(self, rhs: Vector<T>) -> Self::Output {
+    Self::new(self.x - rhs.x, self.y - rhs.y)
+  }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+  type Output = Vector<T>;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    Vector::new(self.x - rhs.x, self.y - rhs.y)
+  }
+}
+
+impl<T: AddAssign> AddAssign<Vector<T>> for Point<T> {
+  fn add_assign(&mut self, rhs: Vector<T>) {
+    self.x += rhs.x;
+    self.y += rhs.y;
+  }
+}
+
+impl<T: SubAssign> SubAssign<Vector<T>> for Point<T> {
+  fn sub_assign(&mut self, rhs: Vector<T>) {
+    self.x -= rhs.x;
+    self.y -= rhs.y;
+  }
+}
+
+impl<T: Signed> Add<Direction> for Point<T> {
+  type Output = Self;
+
+  fn add(self, dir: Direction) -> Self::Output {
+    self + dir.vector()
+  }
+}
+
+impl<T: Signed + AddAssign> AddAssign<Direction> for Point<T> {
+  fn add_assign(&mut self, dir: Direction) {
+    *self += dir.vector();
+  }
+}
+
+impl<T: Add<Output = T>> Add for Vector<T> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    Self::new(self.x + rhs.x, self.y + rhs.y)
+  }
+}
+
+impl<T: Sub<Output = T>> Sub for Vector<T> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    Self::new(self.x - rhs.x, self.y - rhs.y)
+  }
+}
+
+impl<T: Neg<Output = T>> Neg for Vector<T> {
+  type Output = Self;
+
+  fn neg(self) -> Self::Output {
+    Self::new(-self.x, -self.y)
+  }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Vector<T> {
+  type Output = Self;
+
+  fn mul(self, rhs: T) -> Self::Output {
+    Self::new(self.x * rhs, self.y * rhs)
+  }
+}
+
+// Screen coordinates: `y` grows downwards, so `Up` is `(0, -1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+  Up,
+  Right,
+  Down,
+  Left,
+}
+
+impl Direction {
+  pub const fn variants() -> [Self; 4] {
+    [Self::Up, Self::Right, Self::Down, Self::Left]
+  }
+
+  pub fn vector<T: Signed>(self) -> Vector<T> {
+    match self {
+      Self::Up => Vector::new(T::zero(), -T::one()),
+      Self::Right => Vector::new(T::one(), T::zero()),
+      Self::Down => Vector::new(T::zero(), T::one()),
+      Self::Left => Vector::new(-T::one(), T::zero()),
+    }
+  }
+
+  pub fn step<T: Signed>(self, pos: Point<T>) -> Point<T> {
+    pos + self
+  }
+
+  pub const fn turn_right(self) -> Self {
+    match self {
+      Self::Up => Self::Right,
+      Self::Right => Self::Down,
+      Self::Down => Self::Left,
+      Self::Left => Self::Up,
+    }
+  }
+
+  pub const fn turn_left(self) -> Self {
+    match self {
+      Self::Up => Self::Left,
+      Self::Right => Self::Up,
+      Self::Down => Self::Right,
+      Self::Left => Self::Down,
+    }
+  }
+
+  pub const fn flip(self) -> Self {
+    match self {
+      Self::Up => Self::Down,
+      Self::Right => Self::Left,
+      Self::Down => Self::Up,
+      Self::Left => Self::Right,
+    }
+  }
+
+  // Positive quarter turns are clockwise.
+  pub fn rotate(self, quarter_turns: isize) -> Self {
+    let variants = Self::variants();
+    variants[(self as isize + quarter_turns).rem_euclid(4) as usize]
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidDirection(pub char);
+
+impl TryFrom<char> for Direction {
+  type Error = InvalidDirection;
+
+  fn try_from(c: char) -> Result<Self, Self::Error> {
+    match c {
+      'U' | 'N' | '^' => Ok(Self::Up),
+      'R' | 'E' | '>' => Ok(Self::Right),
+      'D' | 'S' | 'v' => Ok(Self::Down),
+      'L' | 'W' | '<' => Ok(Self::Left),
+      c => Err(InvalidDirection(c)),
+    }
+  }
+}
+
+impl From<Direction> for char {
+  fn from(dir: Direction) -> Self {
+    match dir {
+      Direction::Up => '^',
+      Direction::Right => '>',
+      Direction::Down => 'v',
+      Direction::Left => '<',
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn points_and_vectors_compose() {
+    let a = Point::new(3, 4);
+    let b = Point::new(1, 7);
+
+    assert_eq!(a - b, Vector::new(2, -3));
+    assert_eq!(b + (a - b), a);
+    assert_eq!(a + Vector::new(1, 1) * 2, Point::new(5, 6));
+    assert_eq!(-Vector::new(1, -2), Vector::new(-1, 2));
+    assert_eq!(<[i32; 2]>::from(a), [3, 4]);
+  }
+
+  #[test]
+  fn directions_step_and_turn() {
+    let origin = Point::new(0i64, 0);
+
+    assert_eq!(Direction::Up.step(origin), Point::new(0, -1));
+    assert_eq!(origin + Direction::Left, Point::new(-1, 0));
+    assert_eq!(Direction::Up.turn_right(), Direction::Right);
+    assert_eq!(Direction::Up.turn_left(), Direction::Left);
+    assert_eq!(Direction::Left.flip(), Direction::Right);
+    assert_eq!(Direction::Up.rotate(-1), Direction::Left);
+    assert_eq!(Direction::Down.rotate(6), Direction::Up);
+
+    for dir in Direction::variants() {
+      assert_eq!(dir.rotate(1), dir.turn_right());
+      assert_eq!(dir.rotate(2), dir.flip());
+      assert_eq!(Direction::try_from(char::from(dir)), Ok(dir));
+    }
+
+    assert_eq!(Direction::try_from('x'), Err(InvalidDirection('x')));
+  }
+}
@@ -1,40 +1,65 @@
 use std::{fmt::Display, str::FromStr};
 
-#[derive(PartialEq, Eq, Hash, Debug)]
+use super::{geom::Point, ParseError, ParseErrorKind};
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub struct Grid<T> {
   data: Vec<T>,
   rows: usize,
+  cols: usize,
 }
 
+const NEIGHBORS4: [[isize; 2]; 4] = [[0, -1], [-1, 0], [1, 0], [0, 1]];
+
+const NEIGHBORS8: [[isize; 2]; 8] = [
+  [-1, -1],
+  [0, -1],
+  [1, -1],
+  [-1, 0],
+  [1, 0],
+  [-1, 1],
+  [0, 1],
+  [1, 1],
+];
+
 impl<T> Grid<T> {
+  pub fn from_fn(
+    rows: usize,
+    cols: usize,
+    mut f: impl FnMut([isize; 2]) -> T,
+  ) -> Self {
+    let data = (0..rows as isize)
+      .flat_map(|y| (0..cols as isize).map(move |x| [x, y]))
+      .map(&mut f)
+      .collect();
+
+    Self { data, rows, cols }
+  }
+
   pub const fn rows(&self) -> isize {
     self.rows as isize
   }
 
-  pub fn cols(&self) -> isize {
-    (self.data.len() as isize) / self.rows()
+  pub const fn cols(&self) -> isize {
+    self.cols as isize
   }
 
-  pub fn get(&self, [x, y]: [isize; 2]) -> Option<&T> {
-    let rows = self.rows();
-    let cols = self.cols();
-
+  fn index([x, y]: [isize; 2], rows: isize, cols: isize) -> Option<usize> {
     if (0..rows).contains(&y) && (0..cols).contains(&x) {
-      Some(&self.data[(rows * y + x) as usize])
+      Some((cols * y + x) as usize)
     } else {
       None
     }
   }
 
-  pub fn get_mut(&mut self, [x, y]: [isize; 2]) -> Option<&mut T> {
-    let rows = self.rows();
-    let cols = self.cols();
+  pub fn get(&self, pos: [isize; 2]) -> Option<&T> {
+    let index = Self::index(pos, self.rows(), self.cols())?;
+    Some(&self.data[index])
+  }
 
-    if (0..rows).contains(&y) && (0..cols).contains(&x) {
-      Some(&mut self.data[(rows * y + x) as usize])
-    } else {
-      None
-    }
+  pub fn get_mut(&mut self, pos: [isize; 2]) -> Option<&mut T> {
+    let index = Self::index(pos, self.rows(), self.cols())?;
+    Some(&mut self.data[index])
   }
 
   pub fn keys(&self) -> impl Iterator<Item = [isize; 2]> + '_ {
@@ -54,6 +79,94 @@ impl<T> Grid<T> {
 
     (0..rows).flat_map(move |y| (0..cols).map(move |x| ([x, y], &self[[x, y]])))
   }
+
+  fn neighbors<'a>(
+    &'a self,
+    [x, y]: [isize; 2],
+    offsets: &'a [[isize; 2]],
+  ) -> impl Iterator<Item = ([isize; 2], &'a T)> + 'a {
+    offsets.iter().filter_map(move |[dx, dy]| {
+      let pos = [x + dx, y + dy];
+      Some((pos, self.get(pos)?))
+    })
+  }
+
+  pub fn neighbors4(
+    &self,
+    pos: [isize; 2],
+  ) -> impl Iterator<Item = ([isize; 2], &T)> + '_ {
+    self.neighbors(pos, &NEIGHBORS4)
+  }
+
+  pub fn neighbors8(
+    &self,
+    pos: [isize; 2],
+  ) -> impl Iterator<Item = ([isize; 2], &T)> + '_ {
+    self.neighbors(pos, &NEIGHBORS8)
+  }
+
+  pub fn row(&self, y: isize) -> impl DoubleEndedIterator<Item = &T> {
+    let range = if (0..self.rows()).contains(&y) {
+      let start = y as usize * self.cols;
+      start..start + self.cols
+    } else {
+      0..0
+    };
+
+    self.data[range].iter()
+  }
+
+  pub fn col(&self, x: isize) -> impl DoubleEndedIterator<Item = &T> {
+    let data = if (0..self.cols()).contains(&x) {
+      &self.data[x as usize..]
+    } else {
+      &[]
+    };
+
+    data.iter().step_by(self.cols.max(1))
+  }
+
+  pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+    Grid {
+      data: self.data.iter().map(&mut f).collect(),
+      rows: self.rows,
+      cols: self.cols,
+    }
+  }
+}
+
+impl<T: Clone> Grid<T> {
+  pub fn transpose(&self) -> Self {
+    Self::from_fn(self.cols, self.rows, |[x, y]| self[[y, x]].clone())
+  }
+
+  pub fn rotate_cw(&self) -> Self {
+    let rows = self.rows();
+    Self::from_fn(self.cols, self.rows, |[x, y]| {
+      self[[y, rows - 1 - x]].clone()
+    })
+  }
+
+  pub fn rotate_ccw(&self) -> Self {
+    let cols = self.cols();
+    Self::from_fn(self.cols, self.rows, |[x, y]| {
+      self[[cols - 1 - y, x]].clone()
+    })
+  }
+
+  pub fn flip_h(&self) -> Self {
+    let cols = self.cols();
+    Self::from_fn(self.rows, self.cols, |[x, y]| {
+      self[[cols - 1 - x, y]].clone()
+    })
+  }
+
+  pub fn flip_v(&self) -> Self {
+    let rows = self.rows();
+    Self::from_fn(self.rows, self.cols, |[x, y]| {
+      self[[x, rows - 1 - y]].clone()
+    })
+  }
 }
 
 impl<T> std::ops::Index<[isize; 2]> for Grid<T> {
@@ -70,9 +183,53 @@ impl<T> std::ops::IndexMut<[isize; 2]> for Grid<T> {
   }
 }
 
+impl<T> std::ops::Index<Point<isize>> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, pos: Point<isize>) -> &Self::Output {
+    &self[<[isize; 2]>::from(pos)]
+  }
+}
+
+impl<T> std::ops::IndexMut<Point<isize>> for Grid<T> {
+  fn index_mut(&mut self, pos: Point<isize>) -> &mut Self::Output {
+    &mut self[<[isize; 2]>::from(pos)]
+  }
+}
+
 #[derive(Debug)]
 pub enum ParseGridError<ItemError> {
-  InvalidItem(ItemError),
+  InvalidItem {
+    row: usize,
+    col: usize,
+    c: char,
+    error: ItemError,
+  },
+  RaggedRow {
+    row: usize,
+    expected: usize,
+    found: usize,
+  },
+}
+
+impl<ItemError> From<ParseGridError<ItemError>> for ParseError {
+  fn from(error: ParseGridError<ItemError>) -> Self {
+    match error {
+      ParseGridError::InvalidItem { row, col, c, .. } => {
+        Self::new(row + 1, col + 1, c, ParseErrorKind::InvalidChar(c))
+      }
+      ParseGridError::RaggedRow {
+        row,
+        expected,
+        found,
+      } => Self::new(
+        row + 1,
+        expected.min(found) + 1,
+        "",
+        ParseErrorKind::Expected("rows of equal length"),
+      ),
+    }
+  }
 }
 
 impl<T> FromStr for Grid<T>
@@ -83,16 +240,36 @@ where
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     let mut rows = 0;
+    let mut cols = None;
     let mut data = Vec::new();
 
-    for line in s.lines() {
+    for (row, line) in s.lines().enumerate() {
       rows += 1;
-      for c in line.chars() {
-        data.push(c.try_into().map_err(ParseGridError::InvalidItem)?);
+
+      let start = data.len();
+      for (col, c) in line.chars().enumerate() {
+        let item = c.try_into().map_err(|error| {
+          ParseGridError::InvalidItem { row, col, c, error }
+        })?;
+        data.push(item);
+      }
+
+      let found = data.len() - start;
+      let expected = *cols.get_or_insert(found);
+      if found != expected {
+        return Err(ParseGridError::RaggedRow {
+          row,
+          expected,
+          found,
+        });
       }
     }
 
-    Ok(Self { rows, data })
+    Ok(Self {
+      data,
+      rows,
+      cols: cols.unwrap_or_default(),
+    })
   }
 }
 
@@ -114,3 +291,120 @@ where
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug, PartialEq, Clone)]
+  struct Cell(char);
+
+  impl TryFrom<char> for Cell {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+      if c.is_ascii_alphanumeric() {
+        Ok(Self(c))
+      } else {
+        Err(c)
+      }
+    }
+  }
+
+  impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      write!(f, "{}", self.0)
+    }
+  }
+
+  #[test]
+  fn indexes_rectangular_grids() {
+    let grid: Grid<Cell> = "abc\ndef\n".parse().unwrap();
+
+    assert_eq!((grid.rows(), grid.cols()), (2, 3));
+    assert_eq!(grid[[2, 0]], Cell('c'));
+    assert_eq!(grid[[0, 1]], Cell('d'));
+    assert_eq!(grid.get([0, 2]), None);
+    assert_eq!(grid.get([3, 0]), None);
+    assert_eq!(grid.to_string(), "abc\ndef\n");
+
+    let grid: Grid<Cell> = "ab\ncd\nef\n".parse().unwrap();
+
+    assert_eq!((grid.rows(), grid.cols()), (3, 2));
+    assert_eq!(grid[[1, 2]], Cell('f'));
+    assert!(grid.iter().map(|(_, cell)| cell.0).eq("abcdef".chars()));
+  }
+
+  fn grid(s: &str) -> Grid<Cell> {
+    s.parse().unwrap()
+  }
+
+  #[test]
+  fn iterates_neighbors() {
+    let grid = grid("abc\ndef\n");
+
+    let neighbors4 = |pos| -> String {
+      grid.neighbors4(pos).map(|(_, cell)| cell.0).collect()
+    };
+    let neighbors8 = |pos| -> String {
+      grid.neighbors8(pos).map(|(_, cell)| cell.0).collect()
+    };
+
+    assert_eq!(neighbors4([0, 0]), "bd");
+    assert_eq!(neighbors4([1, 1]), "bdf");
+    assert_eq!(neighbors8([1, 0]), "acdef");
+    assert_eq!(neighbors8([2, 1]), "bce");
+    assert_eq!(
+      grid
+        .neighbors4([2, 0])
+        .map(|(pos, _)| pos)
+        .collect::<Vec<_>>(),
+      [[1, 0], [2, 1]]
+    );
+  }
+
+  #[test]
+  fn iterates_rows_and_cols() {
+    let grid = grid("abc\ndef\n");
+
+    let chars = |it: &mut dyn Iterator<Item = &Cell>| -> String {
+      it.map(|cell| cell.0).collect()
+    };
+
+    assert_eq!(chars(&mut grid.row(1)), "def");
+    assert_eq!(chars(&mut grid.row(1).rev()), "fed");
+    assert_eq!(chars(&mut grid.row(2)), "");
+    assert_eq!(chars(&mut grid.col(0)), "ad");
+    assert_eq!(chars(&mut grid.col(2).rev()), "fc");
+    assert_eq!(chars(&mut grid.col(-1)), "");
+  }
+
+  #[test]
+  fn transforms_grids() {
+    let grid = grid("abc\ndef\n");
+
+    assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+    assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+    assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad\n");
+    assert_eq!(grid.flip_h().to_string(), "cba\nfed\n");
+    assert_eq!(grid.flip_v().to_string(), "def\nabc\n");
+    assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+
+    let upper = grid.map(|cell| Cell(cell.0.to_ascii_uppercase()));
+    assert_eq!(upper.to_string(), "ABC\nDEF\n");
+  }
+
+  #[test]
+  fn rejects_ragged_rows() {
+    let err = "abc\nde\nfgh\n".parse::<Grid<Cell>>().unwrap_err();
+
+    assert!(matches!(
+      err,
+      ParseGridError::RaggedRow {
+        row: 1,
+        expected: 3,
+        found: 2
+      }
+    ));
+  }
+}
@@ -1,7 +1,11 @@
+pub mod geom;
 mod grid;
 mod lending_iterator;
+mod parse_error;
 mod regex_ext;
+pub mod search;
 
-pub use grid::Grid;
+pub use grid::{Grid, ParseGridError};
 pub use lending_iterator::LendingIterator;
+pub use parse_error::{parse_number, ParseError, ParseErrorKind};
 pub use regex_ext::RegexExt;
@@ -0,0 +1,117 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+  Expected(&'static str),
+  InvalidChar(char),
+  InvalidNumber,
+}
+
+impl Display for ParseErrorKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Expected(expected) => write!(f, "expected {expected}"),
+      Self::InvalidChar(c) => write!(f, "invalid character {c:?}"),
+      Self::InvalidNumber => write!(f, "invalid number"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+  pub line: usize,
+  pub column: usize,
+  pub snippet: String,
+  pub kind: ParseErrorKind,
+}
+
+fn location(source: &str, snippet: &str) -> Option<(usize, usize)> {
+  let offset =
+    (snippet.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+  let before = source.get(..offset)?;
+  let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+  let line = before.matches('\n').count() + 1;
+  let column = before[line_start..].chars().count() + 1;
+  Some((line, column))
+}
+
+impl ParseError {
+  pub fn new(
+    line: usize,
+    column: usize,
+    snippet: impl Into<String>,
+    kind: ParseErrorKind,
+  ) -> Self {
+    Self {
+      line,
+      column,
+      snippet: snippet.into(),
+      kind,
+    }
+  }
+
+  // `snippet` must be a subslice of `source`, which is how the location is
+  // recovered; anything else is reported at line 0, column 0.
+  pub fn at(source: &str, snippet: &str, kind: ParseErrorKind) -> Self {
+    let (line, column) = location(source, snippet).unwrap_or((0, 0));
+    Self::new(line, column, snippet, kind)
+  }
+
+  pub fn expected(source: &str, snippet: &str, expected: &'static str) -> Self {
+    Self::at(source, snippet, ParseErrorKind::Expected(expected))
+  }
+
+  // Reports the first character of `rest` as invalid.
+  pub fn invalid_char(source: &str, rest: &str) -> Self {
+    let c = rest.chars().next().unwrap_or_default();
+    let snippet = &rest[..rest.len().min(c.len_utf8())];
+    Self::at(source, snippet, ParseErrorKind::InvalidChar(c))
+  }
+
+  // Moves an error reported relative to `context` to its location in
+  // `source`, for parsers that only see a slice of the whole input.
+  pub fn within(self, source: &str, context: &str) -> Self {
+    let Some((line, column)) = location(source, context) else {
+      return self;
+    };
+
+    if self.line == 0 {
+      return self;
+    }
+
+    Self {
+      line: line + self.line - 1,
+      column: if self.line == 1 {
+        column + self.column - 1
+      } else {
+        self.column
+      },
+      ..self
+    }
+  }
+}
+
+impl Display for ParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "line {}, column {}: {}",
+      self.line, self.column, self.kind
+    )?;
+
+    match self.kind {
+      ParseErrorKind::InvalidChar(_) => Ok(()),
+      _ => write!(f, ", found {:?}", self.snippet),
+    }
+  }
+}
+
+impl Error for ParseError {}
+
+pub fn parse_number<T: FromStr>(
+  source: &str,
+  s: &str,
+) -> Result<T, ParseError> {
+  s.parse()
+    .map_err(|_| ParseError::at(source, s, ParseErrorKind::InvalidNumber))
+}
@@ -0,0 +1,350 @@
+use std::{
+  cmp::Ordering,
+  collections::{hash_map, BinaryHeap, HashMap, VecDeque},
+  hash::Hash,
+  ops::Add,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S, C> {
+  pub cost: C,
+  pub path: Vec<S>,
+}
+
+#[derive(PartialEq, Eq)]
+struct QueueEntry<C> {
+  estimate: C,
+  cost: C,
+  index: usize,
+}
+
+impl<C: Ord> Ord for QueueEntry<C> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self
+      .estimate
+      .cmp(&other.estimate)
+      .then_with(|| self.cost.cmp(&other.cost))
+      .reverse()
+  }
+}
+
+impl<C: Ord> PartialOrd for QueueEntry<C> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+struct Node<S, C> {
+  state: S,
+  cost: C,
+  parent: Option<usize>,
+}
+
+fn reconstruct_path<S: Clone, C>(
+  nodes: &[Node<S, C>],
+  mut index: usize,
+) -> Vec<S> {
+  let mut path = vec![nodes[index].state.clone()];
+
+  while let Some(parent) = nodes[index].parent {
+    path.push(nodes[parent].state.clone());
+    index = parent;
+  }
+
+  path.reverse();
+  path
+}
+
+// Maps each state to its node, registering new states as they are found.
+trait NodeIndex<S> {
+  fn find_or_insert(&mut self, state: &S, next: usize) -> Option<usize>;
+}
+
+impl<S: Clone + Eq + Hash> NodeIndex<S> for HashMap<S, usize> {
+  fn find_or_insert(&mut self, state: &S, next: usize) -> Option<usize> {
+    match self.entry(state.clone()) {
+      hash_map::Entry::Occupied(entry) => Some(*entry.get()),
+      hash_map::Entry::Vacant(entry) => {
+        entry.insert(next);
+        None
+      }
+    }
+  }
+}
+
+struct DenseIndex<F> {
+  slots: Vec<usize>,
+  index: F,
+}
+
+impl<S, F: FnMut(&S) -> usize> NodeIndex<S> for DenseIndex<F> {
+  fn find_or_insert(&mut self, state: &S, next: usize) -> Option<usize> {
+    let slot = &mut self.slots[(self.index)(state)];
+    if *slot == usize::MAX {
+      *slot = next;
+      None
+    } else {
+      Some(*slot)
+    }
+  }
+}
+
+fn search<S, C, I>(
+  mut indices: impl NodeIndex<S>,
+  starts: impl IntoIterator<Item = S>,
+  mut successors: impl FnMut(&S) -> I,
+  mut heuristic: impl FnMut(&S) -> C,
+  mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+  S: Clone,
+  C: Copy + Ord + Add<Output = C> + num::Zero,
+  I: IntoIterator<Item = (S, C)>,
+{
+  let mut nodes = Vec::<Node<S, C>>::new();
+  let mut queue = BinaryHeap::<QueueEntry<C>>::new();
+
+  for state in starts {
+    let index = nodes.len();
+    if indices.find_or_insert(&state, index).is_none() {
+      queue.push(QueueEntry {
+        estimate: heuristic(&state),
+        cost: C::zero(),
+        index,
+      });
+      nodes.push(Node {
+        state,
+        cost: C::zero(),
+        parent: None,
+      });
+    }
+  }
+
+  while let Some(QueueEntry { cost, index, .. }) = queue.pop() {
+    if cost > nodes[index].cost {
+      continue;
+    }
+
+    if is_goal(&nodes[index].state) {
+      return Some(SearchResult {
+        cost,
+        path: reconstruct_path(&nodes, index),
+      });
+    }
+
+    for (next_state, step_cost) in successors(&nodes[index].state) {
+      let next_cost = cost + step_cost;
+
+      let next_index = match indices.find_or_insert(&next_state, nodes.len()) {
+        Some(next_index) => {
+          let node = &mut nodes[next_index];
+          if next_cost >= node.cost {
+            continue;
+          }
+          node.cost = next_cost;
+          node.parent = Some(index);
+          next_index
+        }
+        None => {
+          nodes.push(Node {
+            state: next_state,
+            cost: next_cost,
+            parent: Some(index),
+          });
+          nodes.len() - 1
+        }
+      };
+
+      queue.push(QueueEntry {
+        estimate: next_cost + heuristic(&nodes[next_index].state),
+        cost: next_cost,
+        index: next_index,
+      });
+    }
+  }
+
+  None
+}
+
+pub fn a_star<S, C, I>(
+  starts: impl IntoIterator<Item = S>,
+  successors: impl FnMut(&S) -> I,
+  heuristic: impl FnMut(&S) -> C,
+  is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+  S: Clone + Eq + Hash,
+  C: Copy + Ord + Add<Output = C> + num::Zero,
+  I: IntoIterator<Item = (S, C)>,
+{
+  let indices = HashMap::<S, usize>::new();
+  search(indices, starts, successors, heuristic, is_goal)
+}
+
+// Like `a_star`, for states that `index` numbers densely below `len`;
+// looking them up in a table is much cheaper than hashing them.
+pub fn a_star_dense<S, C, I>(
+  len: usize,
+  index: impl FnMut(&S) -> usize,
+  starts: impl IntoIterator<Item = S>,
+  successors: impl FnMut(&S) -> I,
+  heuristic: impl FnMut(&S) -> C,
+  is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+  S: Clone,
+  C: Copy + Ord + Add<Output = C> + num::Zero,
+  I: IntoIterator<Item = (S, C)>,
+{
+  let indices = DenseIndex {
+    slots: vec![usize::MAX; len],
+    index,
+  };
+  search(indices, starts, successors, heuristic, is_goal)
+}
+
+pub fn dijkstra<S, C, I>(
+  starts: impl IntoIterator<Item = S>,
+  successors: impl FnMut(&S) -> I,
+  is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+  S: Clone + Eq + Hash,
+  C: Copy + Ord + Add<Output = C> + num::Zero,
+  I: IntoIterator<Item = (S, C)>,
+{
+  a_star(starts, successors, |_| C::zero(), is_goal)
+}
+
+pub fn dijkstra_dense<S, C, I>(
+  len: usize,
+  index: impl FnMut(&S) -> usize,
+  starts: impl IntoIterator<Item = S>,
+  successors: impl FnMut(&S) -> I,
+  is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+  S: Clone,
+  C: Copy + Ord + Add<Output = C> + num::Zero,
+  I: IntoIterator<Item = (S, C)>,
+{
+  a_star_dense(len, index, starts, successors, |_| C::zero(), is_goal)
+}
+
+pub fn bfs<S, I>(
+  starts: impl IntoIterator<Item = S>,
+  mut successors: impl FnMut(&S) -> I,
+  mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, usize>>
+where
+  S: Clone + Eq + Hash,
+  I: IntoIterator<Item = S>,
+{
+  let mut nodes = Vec::<Node<S, usize>>::new();
+  let mut indices = HashMap::<S, usize>::new();
+  let mut queue = VecDeque::<usize>::new();
+
+  for state in starts {
+    if let hash_map::Entry::Vacant(entry) = indices.entry(state.clone()) {
+      entry.insert(nodes.len());
+      queue.push_back(nodes.len());
+      nodes.push(Node {
+        state,
+        cost: 0,
+        parent: None,
+      });
+    }
+  }
+
+  while let Some(index) = queue.pop_front() {
+    if is_goal(&nodes[index].state) {
+      return Some(SearchResult {
+        cost: nodes[index].cost,
+        path: reconstruct_path(&nodes, index),
+      });
+    }
+
+    for next_state in successors(&nodes[index].state) {
+      if let hash_map::Entry::Vacant(entry) = indices.entry(next_state) {
+        let next_index = nodes.len();
+        nodes.push(Node {
+          state: entry.key().clone(),
+          cost: nodes[index].cost + 1,
+          parent: Some(index),
+        });
+        entry.insert(next_index);
+        queue.push_back(next_index);
+      }
+    }
+  }
+
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn edges(node: &char) -> Vec<(char, u32)> {
+    match node {
+      'a' => vec![('b', 7), ('c', 9), ('f', 14)],
+      'b' => vec![('a', 7), ('c', 10), ('d', 15)],
+      'c' => vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)],
+      'd' => vec![('b', 15), ('c', 11), ('e', 6)],
+      'e' => vec![('d', 6), ('f', 9)],
+      'f' => vec![('a', 14), ('c', 2), ('e', 9)],
+      _ => vec![],
+    }
+  }
+
+  #[test]
+  fn dijkstra_finds_cheapest_path() {
+    let result = dijkstra(['a'], edges, |&node| node == 'e').unwrap();
+    assert_eq!(result.cost, 20);
+    assert_eq!(result.path, ['a', 'c', 'f', 'e']);
+
+    assert_eq!(dijkstra(['a'], edges, |&node| node == 'z'), None);
+
+    let index = |&node: &char| node as usize - 'a' as usize;
+    let dense = dijkstra_dense(6, index, ['a'], edges, |&node| node == 'e');
+    assert_eq!(dense, Some(result));
+  }
+
+  #[test]
+  fn a_star_uses_heuristic() {
+    let walls = ["....", ".##.", "...#", "#..."];
+    let goal = [3i32, 3];
+
+    let successors = |&[x, y]: &[i32; 2]| {
+      [[x + 1, y], [x - 1, y], [x, y + 1], [x, y - 1]]
+        .into_iter()
+        .filter(|&[x, y]| {
+          (0..4).contains(&x)
+            && (0..4).contains(&y)
+            && walls[y as usize].as_bytes()[x as usize] == b'.'
+        })
+        .map(|pos| (pos, 1))
+        .collect::<Vec<_>>()
+    };
+
+    let manhattan =
+      |&[x, y]: &[i32; 2]| (goal[0] - x).abs() + (goal[1] - y).abs();
+
+    let result =
+      a_star([[0, 0]], successors, manhattan, |&pos| pos == goal).unwrap();
+
+    assert_eq!(result.cost, 6);
+    assert_eq!(result.path.len(), 7);
+    assert_eq!(result.path.first(), Some(&[0, 0]));
+    assert_eq!(result.path.last(), Some(&goal));
+  }
+
+  #[test]
+  fn bfs_finds_fewest_steps() {
+    let successors = |&n: &u32| [n + 1, n * 2];
+
+    let result = bfs([1], successors, |&n| n == 10).unwrap();
+    assert_eq!(result.cost, 4);
+    assert_eq!(result.path, [1, 2, 4, 5, 10]);
+  }
+}